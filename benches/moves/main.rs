@@ -0,0 +1,84 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! How fast `physical::Cube` does face turns. Run it with
+//! `cargo bench --bench moves`.
+//!
+//! It only uses `Cube::new` and the six face turns, so it builds against
+//! the older cubie model too, which worked out every coordinate after every
+//! turn. To compare with it, copy this file and its `[[bench]]` entry into
+//! the commit before the cube was kept as flat arrays (89811d6), run
+//! `cargo bench --bench moves -- --save-baseline cubies` there, then
+//! `cargo bench --bench moves -- --baseline cubies` here. On the machine it
+//! was written on, a thousand turns took about 220µs with the cubie model
+//! and 19µs with the flat arrays.
+
+#![allow(unused_imports)]
+
+#[macro_use]
+extern crate criterion;
+extern crate memmap2;
+extern crate rand;
+
+use criterion::{black_box, Criterion};
+
+#[allow(dead_code)]
+#[path = "../../src/utility/mod.rs"]
+mod utility;
+
+// `cargo bench` builds with `cfg(test)` on but without the test functions,
+// so the modules' tests leave unused imports behind, and those of
+// `physical` reach into `facelets` and `solver`.
+#[allow(dead_code)]
+#[path = "../../src/facelets/mod.rs"]
+mod facelets;
+
+#[allow(dead_code)]
+#[path = "../../src/physical/mod.rs"]
+mod physical;
+
+#[allow(dead_code)]
+#[path = "../../src/solver/mod.rs"]
+mod solver;
+
+/// The number of face turns done in each iteration.
+const TURNS: usize = 1000;
+
+/// The face to turn at each step, in the order F, B, U, D, L, R. Never the
+/// same face twice in a row, so no turns cancel out.
+fn face(i: usize) -> usize {
+    (i * 7 + i / 6) % 6
+}
+
+fn face_turns(c: &mut Criterion) {
+    let turns: [fn(&mut physical::Cube); 6] = [
+        physical::Cube::f,
+        physical::Cube::b,
+        physical::Cube::u,
+        physical::Cube::d,
+        physical::Cube::l,
+        physical::Cube::r,
+    ];
+
+    let mut group = c.benchmark_group("face turns");
+    group.bench_function("cubies", |b| {
+        let mut cube = physical::Cube::new();
+        b.iter(|| {
+            for i in 0..TURNS {
+                turns[face(i)](&mut cube);
+            }
+            black_box(&cube);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, face_turns);
+criterion_main!(benches);
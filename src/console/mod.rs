@@ -12,10 +12,16 @@
 
 use crate::facelets;
 use facelets::IFace;
+use facelets::scheme::ColorScheme;
 use crate::solver;
 
 #[allow(dead_code)]
 fn create_terminal(){
+    let scheme = ColorScheme::configured().unwrap_or_else(|e| {
+        println!("{}; using the Western colours instead.", e);
+        ColorScheme::western()
+    });
+    let codes: Vec<String> = scheme.colours.iter().map(|c| format!("{} for {}", c.code, c.name)).collect();
     // Command line
     //let not_exit = true; // Used to be mut
     loop {
@@ -29,19 +35,27 @@ fn create_terminal(){
                     break;
                 } else if cube.to_ascii_uppercase() == "H" {
                     // Might redo this and make it a better interface in general. @@TODO@@
-                    println!("Insert your U, R, F, D, L, B (each repressenting a different colour of the cube.)The first nine values should represent the Upper face, the next the right face, then the front face, down, left, and finally back. Each face should describe the top left to bottom right facelets. ")
-                } else if cube.len() > 54 {
-                    println!("Your input has more facelets than in a 3x3 rubiks cube at: {}. Please insure you have 54 facelets", cube.len())
-                } else if cube.len() < 54 {
-                    println!("Your input has less facelets than in a 3x3 rubiks cube at: {}. Please insure you have 54 facelets", cube.len())
+                    println!("Insert one letter for the colour of each facelet: {}. The first nine values should represent the Upper face, the next the right face, then the front face, down, left, and finally back. Each face should describe the top left to bottom right facelets. The centre of each face says which colour is which face, so the cube can be held any way up. Set RUBIKS_COLOUR_SCHEME to a preset or a scheme file to use other colours. ", codes.join(", "))
                 } else {
-                    println!("Valid input; looking for moves now!");
-                    let face = facelets::Face::new(&cube);
-                    let (msg, success) = face.return_code_matcher();
-                    print!("{}", msg);
-                    if success {
-                        let mut c_cube = face.turn_into_cube();
-                        print!("{}", solver::complete_search(&mut c_cube))
+                    match scheme.parse(&cube) {
+                        Ok(face) => {
+                            println!("Valid input; looking for moves now!");
+                            let (msg, success) = face.return_code_matcher();
+                            println!("{}", msg);
+                            if success {
+                                let c_cube = face.turn_into_cube();
+                                match solver::complete_search(&c_cube) {
+                                    Ok(result) => println!("Move list: {}", result),
+                                    Err(e) => println!("{}", e),
+                                }
+                            } else {
+                                println!("{}", face.validate());
+                                for suggestion in facelets::suggestions::suggest(&face, 5).iter() {
+                                    println!("Did you mean to {}?", suggestion);
+                                }
+                            }
+                        }
+                        Err(e) => println!("Invalid input: {}. Please try again.", e),
                     }
                 }
             }
@@ -11,12 +11,25 @@
 //! Module regulates the face values and handles converting facelet input into
 //! cubie form so that we can solve the problem.
 
-use physical::corner_cubies::*;
-use physical::edge_cubies::*;
+pub mod moves;
+pub mod scheme;
+pub mod suggestions;
+pub mod validation;
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use physical::corner_cubies::Corner;
+use physical::edge_cubies::Edge;
 use physical::Cube;
 
+use self::validation::ValidationReport;
+
 /// A enum of the different possible face values.
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub enum Facelets {
     U = 0,
     R,
@@ -29,13 +42,88 @@ pub enum Facelets {
 
 pub type RubiksChar = [char; 54];
 pub type RubiksFacelets = [Facelets; 54];
-pub type Face = RubiksFacelets;
+
+/// The 54 facelets of a cube, face by face in the order U, R, F, D, L, B.
+/// Derefs to the array, so it can be indexed like one.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub struct Face(pub RubiksFacelets);
+
+impl Deref for Face {
+    type Target = RubiksFacelets;
+
+    fn deref(&self) -> &RubiksFacelets {
+        &self.0
+    }
+}
+
+impl DerefMut for Face {
+    fn deref_mut(&mut self) -> &mut RubiksFacelets {
+        &mut self.0
+    }
+}
+
+/// What is wrong with a `Face` or the string it was read from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FaceError {
+    /// The string is not 54 characters long.
+    Length(usize),
+    /// A character that is not one of U, R, F, D, L or B. `position` counts
+    /// characters from 0, so it is also the index of the facelet.
+    BadCharacter { position: usize, character: char },
+    /// A facelet index past 53.
+    OutOfRange(usize),
+    /// Two centres with the same symbol, so the faces cannot be told apart.
+    SameCentres(char),
+    /// A symbol that is not on any of the centres.
+    UnknownSymbol { position: usize, character: char },
+}
+
+impl fmt::Display for FaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FaceError::Length(length) => write!(f, "a cube has 54 facelets, not {}", length),
+            FaceError::BadCharacter { position, character } => write!(
+                f,
+                "`{}` at position {} is not one of U, R, F, D, L or B",
+                character, position
+            ),
+            FaceError::OutOfRange(index) => write!(f, "there is no facelet {}, they go from 0 to 53", index),
+            FaceError::SameCentres(symbol) => write!(f, "two centres are both `{}`", symbol),
+            FaceError::UnknownSymbol { position, character } => write!(
+                f,
+                "`{}` at position {} is not on any of the centres",
+                character, position
+            ),
+        }
+    }
+}
+
+impl Error for FaceError {}
+
+impl FromStr for Face {
+    type Err = FaceError;
+
+    fn from_str(s: &str) -> Result<Face, FaceError> {
+        Face::new(s)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Face {
+    type Error = FaceError;
+
+    fn try_from(s: &'a str) -> Result<Face, FaceError> {
+        Face::new(s)
+    }
+}
 
 pub trait IFace {
-    fn new(&str) -> Face;
+    fn new(s: &str) -> Result<Face, FaceError>;
+    fn from_symbols(s: &str) -> Result<Face, FaceError>;
     fn new_clean() -> Face;
-    fn set_facelets(&mut self, index: usize, val: Facelets);
-    fn get_facelets(&self, index: usize) -> Facelets;
+    fn from_cube(c: &Cube) -> Face;
+    fn set_facelets(&mut self, index: usize, val: Facelets) -> Result<(), FaceError>;
+    fn get_facelets(&self, index: usize) -> Option<Facelets>;
+    fn validate(&self) -> ValidationReport;
     fn check_if_can_be_solved(&self) -> usize;
     fn check_all_colours_present(&self) -> bool;
     fn check_corners_colours(&self) -> bool;
@@ -47,44 +135,81 @@ pub trait IFace {
 }
 
 impl IFace for Face {
-    /// Creates a new `Face` from a string of 54 characters. Panics is
-    /// string is invalid.
+    /// Creates a new `Face` from a string of 54 characters, one for each
+    /// facelet. Upper and lower case letters both work. The same as
+    /// `s.parse::<Face>()`.
     ///
+    /// # Parameters
+    /// * `s` - The facelets in the order U, R, F, D, L, B.
     /// # Returns
-    /// * `Face` - Create a mixed up face with the string provided.
-    fn new(s: &str) -> Face {
-        let mut new_face: Face = [Facelets::U; 54];
-
-        if s.chars().count() < 54 {
-            panic!(
-                "Error creating face. Passed string is too short at {}.",
-                s.chars().count()
-            )
-        } else if s.chars().count() > 54 {
-            panic!(
-                "Error creating face. Passed string is too long at {}.",
-                s.chars().count()
-            )
+    /// * `Result<Face, FaceError>` - The face, or the first thing wrong with
+    ///     `s`.
+    fn new(s: &str) -> Result<Face, FaceError> {
+        let mut new_face = Face([Facelets::U; 54]);
+
+        let length = s.chars().count();
+        if length != 54 {
+            return Err(FaceError::Length(length));
         }
         for (i, c) in s.chars().enumerate() {
-            if c.to_ascii_lowercase() == 'u' {
-                new_face.set_facelets(i, Facelets::U);
-            } else if c.to_ascii_lowercase() == 'd' {
-                new_face.set_facelets(i, Facelets::D);
-            } else if c.to_ascii_lowercase() == 'l' {
-                new_face.set_facelets(i, Facelets::L);
-            } else if c.to_ascii_lowercase() == 'r' {
-                new_face.set_facelets(i, Facelets::R);
-            } else if c.to_ascii_lowercase() == 'f' {
-                new_face.set_facelets(i, Facelets::F);
-            } else if c.to_ascii_lowercase() == 'b' {
-                new_face.set_facelets(i, Facelets::B);
-            } else {
-                panic!("Error creating face. Contains weird characters: {}", c)
+            new_face[i] = match c.to_ascii_lowercase() {
+                'u' => Facelets::U,
+                'd' => Facelets::D,
+                'l' => Facelets::L,
+                'r' => Facelets::R,
+                'f' => Facelets::F,
+                'b' => Facelets::B,
+                _ => {
+                    return Err(FaceError::BadCharacter {
+                        position: i,
+                        character: c,
+                    })
+                }
+            };
+        }
+        Ok(new_face)
+    }
+
+    /// Creates a new `Face` from 54 symbols of any kind, such as the first
+    /// letters of the colours. Each face is named by the symbol on its
+    /// centre, U5, R5, F5, D5, L5 and B5, so any colour scheme works and the
+    /// cube can be held in any of its 24 orientations. The faces are still
+    /// read in the order U, R, F, D, L, B as the cube is held. Letters match
+    /// whatever their case.
+    ///
+    /// # Parameters
+    /// * `s` - The symbol on each facelet.
+    /// # Returns
+    /// * `Result<Face, FaceError>` - The face, or the first thing wrong with
+    ///     `s`.
+    fn from_symbols(s: &str) -> Result<Face, FaceError> {
+        let symbols: Vec<char> = s.chars().collect();
+        if symbols.len() != 54 {
+            return Err(FaceError::Length(symbols.len()));
+        }
+
+        let mut centres = ['\0'; 6];
+        for i in 0..6 {
+            let centre = symbols[i * 9 + 4].to_ascii_uppercase();
+            if centres[..i].contains(&centre) {
+                return Err(FaceError::SameCentres(centre));
             }
+            centres[i] = centre;
         }
-        //println!("Face Created!");
-        new_face
+
+        let mut new_face = Face([Facelets::U; 54]);
+        for (i, c) in symbols.iter().enumerate() {
+            match centres.iter().position(|centre| *centre == c.to_ascii_uppercase()) {
+                Some(face) => new_face[i] = FACELET_LIST[face],
+                None => {
+                    return Err(FaceError::UnknownSymbol {
+                        position: i,
+                        character: *c,
+                    })
+                }
+            }
+        }
+        Ok(new_face)
     }
 
     #[allow(dead_code)]
@@ -93,15 +218,15 @@ impl IFace for Face {
     /// # Returns
     /// * `Face` - A pristine cube face.
     fn new_clean() -> Face {
-        let mut new_face: Face = [Facelets::U; 54];
+        let mut new_face = Face([Facelets::U; 54]);
         
         let facelets_vals = [
             Facelets::U,
             Facelets::R,
             Facelets::F,
+            Facelets::D,
             Facelets::L,
             Facelets::B,
-            Facelets::D,
         ];
         for i in 0..6 {
             for j in 0..9 {
@@ -111,32 +236,71 @@ impl IFace for Face {
         new_face
     }
 
+    /// Creates the face showing the stickers of a cube, the inverse of
+    /// `turn_into_cube`.
+    ///
+    /// # Parameters
+    /// * `c` - The `Cube` to colour in.
+    /// # Returns
+    /// * `Face` - The stickers of `c`, with the centres in their usual place.
+    fn from_cube(c: &Cube) -> Face {
+        let mut new_face = Face::new_clean();
+
+        // The sticker a cubie keeps on the U or D face sits `orientation`
+        // places along its list of facelets, the others follow in turn.
+        for i in 0..8 {
+            let colours = corner_colours(c.corners.permutation[i]);
+            let o = c.corners.orientation[i] as usize % 3;
+            for k in 0..3 {
+                new_face[CORNER_INDEXES[i][(k + o) % 3]] = colours[k];
+            }
+        }
+        for i in 0..12 {
+            let colours = edge_colours(c.edges.permutation[i]);
+            let o = c.edges.orientation[i] as usize;
+            for k in 0..2 {
+                new_face[EDGE_INDEXES[i][(k + o) % 2]] = colours[k];
+            }
+        }
+        new_face
+    }
+
     /// A setter method for the facelets arrays in `Cube`. This allows us to
     /// manage the two halfs of the array as one.
     ///
     /// # Parameters
     /// * `index` - The index of which you wish to change. Between 0 and 53
     /// * `val` - The value you wish to change the specific face to.
-    fn set_facelets(&mut self, index: usize, val: Facelets) {
-        if index < 54{
-            self[index] = val;
-        } else {
-            panic!("set_facelets: Outside the index range for facelets. Keep index within 0 and 53. Index found: {}", index);
+    /// # Returns
+    /// * `Result<(), FaceError>` - `FaceError::OutOfRange` if there is no
+    ///     facelet `index`.
+    fn set_facelets(&mut self, index: usize, val: Facelets) -> Result<(), FaceError> {
+        match self.get_mut(index) {
+            Some(facelet) => {
+                *facelet = val;
+                Ok(())
+            }
+            None => Err(FaceError::OutOfRange(index)),
         }
     }
-    
+
     /// A getter method fo rthe facelet arrays in `Cube`. This allows us to
     /// manage the two halfs of the array as one.
     ///
     /// # Parameters
-    /// * `index` - The index of the facelets arrays you wish to access, must
-    ///    be between 0 and 53 or the function will panic.
-    fn get_facelets(&self, index: usize) -> Facelets {
-        if index < 54 {
-            return self[index]
-        } else {
-            panic!("get_facelets: Outside the index range for facelets. Keep index within 0 and 53. Index found: {}", index);
-        }
+    /// * `index` - The index of the facelets arrays you wish to access.
+    /// # Returns
+    /// * `Option<Facelets>` - The facelet, or None if `index` is past 53.
+    fn get_facelets(&self, index: usize) -> Option<Facelets> {
+        self.get(index).cloned()
+    }
+
+    /// Finds everything that stops this face from being a real cube.
+    ///
+    /// # Returns
+    /// * `ValidationReport` - Every problem, with the facelets involved.
+    fn validate(&self) -> ValidationReport {
+        ValidationReport::new(self)
     }
 
     /// A method that checks that the current face is solveable.
@@ -152,32 +316,7 @@ impl IFace for Face {
     ///      5 -> Total Edge Flip is wrong.
     ///      6 -> Total Corner Twist is wrong.
     fn check_if_can_be_solved(&self) -> usize {
-        let return_code;
-        let my_cube = self.turn_into_cube();
-        //println!("Can be solved? {:?}", my_cube);
-        if !self.check_all_colours_present() {
-            return_code = 1
-        }
-        else if !self.check_edges_colours() {
-            return_code = 2
-        }
-        else if !self.check_corners_colours() {
-            return_code = 3
-        }
-        else if my_cube.edge_parity != my_cube.corner_parity {
-            return_code = 4
-        }
-        else if !self.check_edge_flip(my_cube) {
-            return_code = 5
-        }
-        else if !self.check_corner_twist(my_cube) {
-            return_code = 6
-        }
-        else {
-            return_code = 0
-        }
-
-        return_code
+        self.validate().problems.iter().map(|p| p.code()).min().unwrap_or(0)
     }
     
     /// A method that checks if all 6 colours have 9 facelets representing them.
@@ -189,17 +328,17 @@ impl IFace for Face {
         let mut colour_counts = [0, 0, 0, 0, 0, 0];
         let return_bool;
         for i in 0..54 {
-            if self.get_facelets(i) == Facelets::U {
+            if self[i] == Facelets::U {
                 colour_counts[0] = colour_counts[0] + 1
-            } else if self.get_facelets(i) == Facelets::R {
+            } else if self[i] == Facelets::R {
                 colour_counts[1] = colour_counts[1] + 1
-            } else if self.get_facelets(i) == Facelets::F {
+            } else if self[i] == Facelets::F {
                 colour_counts[2] = colour_counts[2] + 1
-            } else if self.get_facelets(i) == Facelets::L {
+            } else if self[i] == Facelets::L {
                 colour_counts[3] = colour_counts[3] + 1
-            } else if self.get_facelets(i) == Facelets::D {
+            } else if self[i] == Facelets::D {
                 colour_counts[4] = colour_counts[4] + 1
-            } else if self.get_facelets(i) == Facelets::B {
+            } else if self[i] == Facelets::B {
                 colour_counts[5] = colour_counts[5] + 1
             }
         }
@@ -221,65 +360,18 @@ impl IFace for Face {
     /// the correct colours.
     ///
     /// # Returns
-    /// * `bool` -> True if all corners exist with the right colours.
+    /// * `bool` -> True if every corner is there once, with its colours in
+    ///   an order a real corner has.
     fn check_corners_colours(&self) -> bool {
-        let mut master_count = 0;
-        let return_bool;
-        for i in 0..8 {
-            let mut current_colours: Vec<Facelets> = Vec::new();
-            for j in 0..3 {
-                current_colours.push(self.get_facelets(CORNER_INDEXES[i][j] as usize));
-            }
-            for k in 0..8 {
-                let mut count = 0;
-                for l in current_colours.iter() {
-                    if corner_colours(CORNER_LIST[k]).contains(l) {
-                        count = count + 1;
-                    }
-                }
-                if count == 3 {
-                    master_count = master_count + 1;
-                }
-            }
-        }
-        if master_count == 8 {
-            return_bool = true
-        } else {
-            return_bool = false
-        }
-        return_bool
+        !self.validate().problems.iter().any(|p| p.code() == 3)
     }
 
     /// A method to test that edges are all there with the right colours.
     ///
     /// # Returns
-    /// * `bool` - True if all the right colours are indeed there.
+    /// * `bool` - True if all 12 edges are there once each.
     fn check_edges_colours(&self) -> bool {
-        let mut master_count = 0;
-        let return_bool;
-        for i in 0..8 {
-            let mut current_colours: Vec<Facelets> = Vec::new();
-            for j in 0..2 {
-                current_colours.push(self.get_facelets(EDGE_INDEXES[i][j] as usize));
-            }
-            for k in 0..12 {
-                let mut count = 0;
-                for l in current_colours.iter() {
-                    if edge_colours(EDGE_LIST[k]).contains(l) {
-                        count = count + 1;
-                    }
-                }
-                if count == 2 {
-                    master_count = master_count + 1;
-                }
-            }
-        }
-        if master_count == 8 {
-            return_bool = true
-        } else {
-            return_bool = false
-        }
-        return_bool
+        !self.validate().problems.iter().any(|p| p.code() == 2)
     }
 
     /// Checks the edge flip of `c`.
@@ -291,8 +383,8 @@ impl IFace for Face {
     fn check_edge_flip(&self, c: Cube) -> bool {
         let mut s = 0;
         let mut return_bool = true;
-        for e in c.edges.iter() {
-            s = s + e.orientation;
+        for o in c.edges.orientation.iter() {
+            s = s + o;
         }
         if s % 2 != 0 {
             return_bool = false;
@@ -309,8 +401,8 @@ impl IFace for Face {
     fn check_corner_twist(&self, c: Cube) -> bool {
         let mut s = 0;
         let mut return_bool = true;
-        for cor in c.corners.iter() {
-            s = s + cor.orientation;
+        for o in c.corners.orientation.iter() {
+            s = s + o;
         }
         if s % 3 != 0 {
             return_bool = false;
@@ -359,52 +451,47 @@ impl IFace for Face {
             let col2: Facelets;
             let mut o: usize = 0;
             for ori in 0..3 {
-                if self.get_facelets(fac[ori]) == Facelets::U
-                    || self.get_facelets(fac[ori]) == Facelets::D
+                if self[fac[ori]] == Facelets::U
+                    || self[fac[ori]] == Facelets::D
                 {
                     o = ori;
                     break;
                 }
             }
-            col1 = self.get_facelets(fac[(o + 1) % 3]);
-            col2 = self.get_facelets(fac[(o + 2) % 3]);
+            col1 = self[fac[(o + 1) % 3]];
+            col2 = self[fac[(o + 2) % 3]];
 
             for c in corners.iter() {
                 let col = corner_colours(*c);
                 if col1 == col[1] && col2 == col[2] {
-                    new_cube.corners[i] = CornerCubie::new(*c);
-                    new_cube.corners[i].orientation = o as i32;
+                    new_cube.corners.permutation[i] = *c;
+                    new_cube.corners.orientation[i] = o as u8;
                     break;
                 }
             }
 
             for (i, _dud) in edges.iter().enumerate() {
                 for e in edges.iter() {
-                    if self.get_facelets(EDGE_INDEXES[i][0]) == edge_colours(*e)[0]
-                        && self.get_facelets(EDGE_INDEXES[i][1]) == edge_colours(*e)[1]
+                    if self[EDGE_INDEXES[i][0]] == edge_colours(*e)[0]
+                        && self[EDGE_INDEXES[i][1]] == edge_colours(*e)[1]
                     {
-                        new_cube.edges[i] = EdgeCubie::new(*e);
-                        new_cube.edges[i].orientation = 0;
-                    } else if self.get_facelets(EDGE_INDEXES[i][0]) == edge_colours(*e)[1]
-                        && self.get_facelets(EDGE_INDEXES[i][1]) == edge_colours(*e)[0]
+                        new_cube.edges.permutation[i] = *e;
+                        new_cube.edges.orientation[i] = 0;
+                    } else if self[EDGE_INDEXES[i][0]] == edge_colours(*e)[1]
+                        && self[EDGE_INDEXES[i][1]] == edge_colours(*e)[0]
                     {
-                        new_cube.edges[i] = EdgeCubie::new(*e);
-                        new_cube.edges[i].orientation = 1;
+                        new_cube.edges.permutation[i] = *e;
+                        new_cube.edges.orientation[i] = 1;
                     }
                 }
             }
         }
 
-        // This is the problem
-        new_cube.coordinate_adjustments();
-        //println!("New cube coordinates adjusted.");
         new_cube
     }
 
     fn return_code_matcher(&self) -> (&'static str, bool) {
-        let return_code = self.check_if_can_be_solved();
-        println!("Return code is: {}", return_code);
-        match return_code {
+        match self.check_if_can_be_solved() {
             0 => {
                 return ("Attempting solve...", true);
             },
@@ -414,7 +501,7 @@ impl IFace for Face {
             4 => return ("Edge and Corner parities aren't equal.", false),
             5 => return ("The total Edge flip is wrong.", false),
             6 => return ("The total Corner twist is wrong.", false),
-            _ => panic!("How on earth did you get a different return code????"),
+            _ => return ("The cube could not be checked.", false),
         }
     }
 }
@@ -479,6 +566,16 @@ pub fn corner_colours(c: Corner) -> [Facelets; 3] {
     }
 }
 
+/// The faces in the order their facelets are stored.
+const FACELET_LIST: [Facelets; 6] = [
+    Facelets::U,
+    Facelets::R,
+    Facelets::F,
+    Facelets::D,
+    Facelets::L,
+    Facelets::B,
+];
+
 const CORNER_LIST: [Corner; 8] = [
     Corner::URF,
     Corner::UFL,
@@ -559,3 +656,154 @@ pub const B6: usize = 50;
 pub const B7: usize = 51;
 pub const B8: usize = 52;
 pub const B9: usize = 53;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use solver::{self, Moves};
+
+    #[test]
+    fn test_from_cube() {
+        let solved = Face::from_cube(&Cube::new());
+        assert_eq!(&solved[..], &Face::new_clean()[..]);
+        assert_eq!(solved.check_if_can_be_solved(), 0);
+
+        let mut c = Cube::new();
+        c.r();
+        let face = Face::from_cube(&c);
+        let expected: Face = "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB".parse().unwrap();
+        assert_eq!(&face[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_from_symbols() {
+        // White on top and green in front.
+        let scheme = |face: &Face| -> String {
+            face.iter()
+                .map(|f| match *f {
+                    Facelets::U => 'W',
+                    Facelets::R => 'R',
+                    Facelets::F => 'G',
+                    Facelets::D => 'Y',
+                    Facelets::L => 'O',
+                    Facelets::B => 'B',
+                })
+                .collect()
+        };
+        let mut rng = StdRng::seed_from_u64(24);
+        let c = Cube::random(&mut rng);
+        let face = Face::from_cube(&c);
+        assert_eq!(Face::from_symbols(&scheme(&face)), Ok(face));
+        assert_eq!(Face::from_symbols(&scheme(&face).to_lowercase()), Ok(face));
+
+        // The same cube held every way up.
+        let ups: [&[Moves]; 6] = [&[], &[Moves::X1], &[Moves::X2], &[Moves::X3], &[Moves::Z1], &[Moves::Z3]];
+        let mut centres = Vec::new();
+        for up in ups.iter() {
+            for turns in 0..4 {
+                let mut held = face;
+                let mut rotated = c;
+                for movement in up.iter().chain([Moves::Y1, Moves::Y1, Moves::Y1][..turns].iter()) {
+                    held = moves::do_move(held, *movement);
+                    rotated = solver::do_move(rotated, *movement);
+                }
+                let read = Face::from_symbols(&scheme(&held)).unwrap();
+                assert_eq!(read, moves::recolour_by_centres(&held));
+                assert_eq!(read.turn_into_cube(), rotated);
+                centres.push((0..6).map(|i| held[i * 9 + 4]).collect::<Vec<_>>());
+            }
+        }
+        centres.sort_by_key(|c| format!("{:?}", c));
+        centres.dedup();
+        assert_eq!(centres.len(), 24);
+
+        let solved = scheme(&Face::new_clean());
+        assert_eq!(Face::from_symbols("WWW"), Err(FaceError::Length(3)));
+        assert_eq!(
+            Face::from_symbols(&solved.replacen("R", "W", 5)),
+            Err(FaceError::SameCentres('W'))
+        );
+        assert_eq!(
+            Face::from_symbols(&solved.replacen("G", "P", 1)),
+            Err(FaceError::UnknownSymbol {
+                position: 18,
+                character: 'P'
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+        assert_eq!(solved.parse::<Face>(), Ok(Face::new_clean()));
+        assert_eq!(Face::try_from(solved.to_lowercase().as_str()), Ok(Face::new_clean()));
+
+        assert_eq!("UUU".parse::<Face>(), Err(FaceError::Length(3)));
+        assert_eq!(Face::new(&solved.repeat(2)), Err(FaceError::Length(108)));
+        assert_eq!(
+            solved.replacen("F", "X", 1).parse::<Face>(),
+            Err(FaceError::BadCharacter {
+                position: 18,
+                character: 'X'
+            })
+        );
+        // Multi-byte characters are counted as one.
+        assert_eq!(
+            format!("é{}", &solved[1..]).parse::<Face>(),
+            Err(FaceError::BadCharacter {
+                position: 0,
+                character: 'é'
+            })
+        );
+
+        let mut face = Face::new_clean();
+        assert_eq!(face.set_facelets(53, Facelets::U), Ok(()));
+        assert_eq!(face.get_facelets(53), Some(Facelets::U));
+        assert_eq!(face.set_facelets(54, Facelets::U), Err(FaceError::OutOfRange(54)));
+        assert_eq!(face.get_facelets(54), None);
+    }
+
+    #[test]
+    fn test_from_cube_round_trip() {
+        // Every value of each coordinate on its own.
+        for i in 0..40320 {
+            let mut c = Cube::new();
+            c.set_corner_permutation(i);
+            c.set_phase_two_edge_permutation(i);
+            assert_eq!(Face::from_cube(&c).turn_into_cube(), c);
+        }
+        for i in 0..2187 {
+            let mut c = Cube::new();
+            c.set_corner_orientation(i);
+            assert_eq!(Face::from_cube(&c).turn_into_cube(), c);
+        }
+        for i in 0..2048 {
+            let mut c = Cube::new();
+            c.set_edge_orientation(i);
+            assert_eq!(Face::from_cube(&c).turn_into_cube(), c);
+        }
+        for i in 0..11880 {
+            let mut c = Cube::new();
+            c.set_ud_sorted_slice(i);
+            assert_eq!(Face::from_cube(&c).turn_into_cube(), c);
+        }
+
+        let mut rng = StdRng::seed_from_u64(19);
+        for _ in 0..20000 {
+            let c = Cube::random(&mut rng);
+            let face = Face::from_cube(&c);
+            assert_eq!(face.check_if_can_be_solved(), 0);
+            assert_eq!(face.turn_into_cube(), c);
+            assert_eq!(&Face::from_cube(&face.turn_into_cube())[..], &face[..]);
+        }
+
+        let mut c = Cube::new();
+        let turns: [fn(&mut Cube); 6] = [Cube::f, Cube::b, Cube::u, Cube::d, Cube::l, Cube::r];
+        for i in 0..200 {
+            turns[(i * 5 + i / 3) % 6](&mut c);
+            assert_eq!(Face::from_cube(&c).turn_into_cube(), c);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Moves done on the 54 stickers of a `Face`.
+//!
+//! This is a second model of the cube, kept apart from the cubies in
+//! `physical` so each can be checked against the other. Every sticker is
+//! given a place in space and the direction it faces, and a move turns the
+//! stickers in some of the layers a quarter turn about an axis. Nothing here
+//! knows about cubies or their orientations.
+//!
+//! The x axis points to the right face, the y axis to the upper face and the
+//! z axis to the front face. Every coordinate is -1, 0 or 1.
+
+use std::sync::OnceLock;
+
+use facelets::{Face, Facelets};
+use solver::Moves;
+
+/// The axis each kind of move turns about, pointing out of the face it
+/// turns like, and the layers it turns. Layer 1 is the face itself, 0 the
+/// middle and -1 the opposite face. In the same order as `Moves`.
+const LAYER_TURNS: [([i32; 3], &[i32]); 18] = [
+    ([0, 0, 1], &[1]),
+    ([0, 0, -1], &[1]),
+    ([0, 1, 0], &[1]),
+    ([0, -1, 0], &[1]),
+    ([-1, 0, 0], &[1]),
+    ([1, 0, 0], &[1]),
+    // M, E and S turn like L, D and F.
+    ([-1, 0, 0], &[0]),
+    ([0, -1, 0], &[0]),
+    ([0, 0, 1], &[0]),
+    // x, y and z turn like R, U and F.
+    ([1, 0, 0], &[-1, 0, 1]),
+    ([0, 1, 0], &[-1, 0, 1]),
+    ([0, 0, 1], &[-1, 0, 1]),
+    ([0, 0, 1], &[0, 1]),
+    ([0, 0, -1], &[0, 1]),
+    ([0, 1, 0], &[0, 1]),
+    ([0, -1, 0], &[0, 1]),
+    ([-1, 0, 0], &[0, 1]),
+    ([1, 0, 0], &[0, 1]),
+];
+
+/// Function that inputs a `Face` and returns a `Face` that has had the
+/// required move applied to it, the same as `solver::do_move` does for a
+/// `Cube`. Slice moves and rotations move the centres.
+///
+/// # Parameters
+/// * `face` - The `Face` to apply the `movement` to.
+/// * `movement` - A `Moves` to apply to `face`.
+/// # Returns
+/// * `Face` - A `Face` with the `movement` applied to it.
+pub fn do_move(face: Face, movement: Moves) -> Face {
+    if movement == Moves::NONE {
+        return face;
+    }
+    let m = movement as usize;
+    let mut face = face;
+    for _ in 0..(m % 3 + 1) {
+        let old = face;
+        for (i, j) in quarter_turns()[m / 3].iter().enumerate() {
+            face[*j] = old[i];
+        }
+    }
+    face
+}
+
+/// Renames every colour after the centre that has it, so a `Face` whose
+/// centres have been moved by slice moves or rotations reads the same way
+/// as the `Cube` it shows. `IFace::turn_into_cube` expects the centres to
+/// be in their usual places.
+///
+/// # Parameters
+/// * `face` - The `Face` to recolour.
+/// # Returns
+/// * `Face` - `face` with the centres back in their usual colours.
+pub fn recolour_by_centres(face: &Face) -> Face {
+    let names = [
+        Facelets::U,
+        Facelets::R,
+        Facelets::F,
+        Facelets::D,
+        Facelets::L,
+        Facelets::B,
+    ];
+    let mut rename = names;
+    for (i, name) in names.iter().enumerate() {
+        rename[face[i * 9 + 4] as usize] = *name;
+    }
+    let mut recoloured = *face;
+    for i in 0..54 {
+        recoloured[i] = rename[face[i] as usize];
+    }
+    recoloured
+}
+
+/// Where each sticker goes after a quarter turn of each kind of move.
+fn quarter_turns() -> &'static [[usize; 54]; 18] {
+    static QUARTER_TURNS: OnceLock<[[usize; 54]; 18]> = OnceLock::new();
+    QUARTER_TURNS.get_or_init(|| {
+        let stickers: Vec<([i32; 3], [i32; 3])> = (0..54).map(sticker).collect();
+        let mut turns = [[0; 54]; 18];
+        for (k, (axis, layers)) in LAYER_TURNS.iter().enumerate() {
+            for i in 0..54 {
+                let (place, normal) = stickers[i];
+                let moved = if layers.contains(&dot(*axis, place)) {
+                    (rotate(*axis, place), rotate(*axis, normal))
+                } else {
+                    (place, normal)
+                };
+                turns[k][i] = stickers.iter().position(|s| *s == moved).unwrap();
+            }
+        }
+        turns
+    })
+}
+
+/// The place of a sticker and the direction it faces. Each face is read in
+/// rows from the top left, looking straight at it with the upper face on
+/// top, or the back face on top for the upper face and the front face on
+/// top for the down face.
+///
+/// # Parameters
+/// * `index` - The index of the sticker in a `Face`, between 0 and 53.
+/// # Returns
+/// * `([i32; 3], [i32; 3])` - Where the sticker is and the way it faces.
+fn sticker(index: usize) -> ([i32; 3], [i32; 3]) {
+    let row = (index % 9 / 3) as i32;
+    let column = (index % 3) as i32;
+    match index / 9 {
+        0 => ([column - 1, 1, row - 1], [0, 1, 0]),
+        1 => ([1, 1 - row, 1 - column], [1, 0, 0]),
+        2 => ([column - 1, 1 - row, 1], [0, 0, 1]),
+        3 => ([column - 1, -1, 1 - row], [0, -1, 0]),
+        4 => ([-1, 1 - row, column - 1], [-1, 0, 0]),
+        _ => ([1 - column, 1 - row, -1], [0, 0, -1]),
+    }
+}
+
+fn dot(a: [i32; 3], b: [i32; 3]) -> i32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Turns `v` a quarter turn clockwise, looking down `axis` from outside
+/// the cube. That is `axis (axis . v) - axis x v`.
+fn rotate(axis: [i32; 3], v: [i32; 3]) -> [i32; 3] {
+    let along = dot(axis, v);
+    let cross = [
+        axis[1] * v[2] - axis[2] * v[1],
+        axis[2] * v[0] - axis[0] * v[2],
+        axis[0] * v[1] - axis[1] * v[0],
+    ];
+    [
+        axis[0] * along - cross[0],
+        axis[1] * along - cross[1],
+        axis[2] * along - cross[2],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use facelets::{IFace, CORNER_INDEXES, EDGE_INDEXES};
+    use physical::Cube;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use solver::{self, inverse_move, ALL_MOVES};
+
+    #[test]
+    fn test_stickers() {
+        // The three stickers of each corner meet at the same place.
+        for corner in CORNER_INDEXES.iter() {
+            assert_eq!(sticker(corner[0]).0, sticker(corner[1]).0);
+            assert_eq!(sticker(corner[0]).0, sticker(corner[2]).0);
+        }
+        for edge in EDGE_INDEXES.iter() {
+            assert_eq!(sticker(edge[0]).0, sticker(edge[1]).0);
+        }
+
+        // R takes the front of the right layer to the top.
+        assert_eq!(rotate([1, 0, 0], [1, 0, 1]), [1, 1, 0]);
+        for movement in ALL_MOVES.iter() {
+            let face = do_move(Face::new_clean(), *movement);
+            assert!(face.check_all_colours_present());
+            let undone = do_move(face, inverse_move(*movement));
+            assert_eq!(&undone[..], &Face::new_clean()[..]);
+        }
+    }
+
+    /// Runs random moves on both models of the cube, checking they agree
+    /// after every move.
+    #[test]
+    fn test_against_cubies() {
+        let mut rng = StdRng::seed_from_u64(20);
+        for _ in 0..200 {
+            let mut face = Face::new_clean();
+            let mut c = Cube::new();
+            for _ in 0..50 {
+                let movement = ALL_MOVES[rng.gen_range(0..ALL_MOVES.len())];
+                face = do_move(face, movement);
+                c = solver::do_move(c, movement);
+                let recoloured = recolour_by_centres(&face);
+                assert_eq!(recoloured.turn_into_cube(), c, "after {:?}", movement);
+                assert_eq!(&Face::from_cube(&c)[..], &recoloured[..]);
+            }
+        }
+    }
+}
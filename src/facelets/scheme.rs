@@ -0,0 +1,468 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! The colours of a cube, and which face each of them is on.
+//!
+//! Every front end draws and reads cubes through a `ColorScheme`, so they
+//! all agree. A few schemes are built in, and others can be read from a
+//! file with one line for each face:
+//!
+//! ```text
+//! # Comments start with a hash.
+//! name = Western
+//! U = white #FFFFFF W
+//! R = red #B71234 R
+//! F = green #009B48 G
+//! D = yellow #FFD500 Y
+//! L = orange #FF5800 O
+//! B = blue #0046AD B
+//! ```
+//!
+//! Each face has the name of its colour, which may have spaces in it, the
+//! colour as hex RGB, and the letter typed for it.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use facelets::{Face, FaceError, Facelets, IFace, FACELET_LIST};
+
+/// One colour of a scheme.
+///
+/// # Variables
+/// * `name` - What the colour is called.
+/// * `rgb` - The colour to draw it in.
+/// * `code` - The letter typed for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Colour {
+    pub name: String,
+    pub rgb: [u8; 3],
+    pub code: char,
+}
+
+impl Colour {
+    fn new(name: &str, rgb: u32, code: char) -> Colour {
+        Colour {
+            name: name.to_string(),
+            rgb: [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8],
+            code,
+        }
+    }
+
+    /// The colour as red, green, blue and alpha between 0 and 1, the way
+    /// the gui draws it.
+    pub fn rgba(&self) -> [f32; 4] {
+        [
+            self.rgb[0] as f32 / 255.0,
+            self.rgb[1] as f32 / 255.0,
+            self.rgb[2] as f32 / 255.0,
+            1.0,
+        ]
+    }
+}
+
+/// The six colours of a cube, by the face they are on when it is solved.
+///
+/// # Variables
+/// * `name` - What the scheme is called.
+/// * `colours` - The colour of each face, in the order U, R, F, D, L, B.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub name: String,
+    pub colours: [Colour; 6],
+}
+
+/// Why a `ColorScheme` could not be read or is not a real cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeError {
+    /// The file could not be read.
+    Io(String),
+    /// A line that could not be understood, counting from 1.
+    Syntax { line: usize, text: String },
+    /// A face that has no colour.
+    MissingFace(Facelets),
+    /// A face given a colour twice.
+    RepeatedFace(Facelets),
+    /// Two faces with the same name.
+    SameName(String),
+    /// Two faces with the same letter, ignoring case.
+    SameCode(char),
+    /// A letter that cannot be typed as one facelet, such as a space.
+    BadCode(char),
+    /// Two colours on opposite faces that are next to each other on every
+    /// real cube.
+    Opposites(String, String),
+}
+
+impl fmt::Display for SchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SchemeError::Io(ref message) => write!(f, "{}", message),
+            SchemeError::Syntax { line, ref text } => write!(f, "line {} is not a face: `{}`", line, text),
+            SchemeError::MissingFace(face) => write!(f, "the {:?} face has no colour", face),
+            SchemeError::RepeatedFace(face) => write!(f, "the {:?} face is given more than once", face),
+            SchemeError::SameName(ref name) => write!(f, "two faces are both {}", name),
+            SchemeError::SameCode(code) => write!(f, "two faces are both typed `{}`", code),
+            SchemeError::BadCode(code) => write!(f, "`{}` cannot be typed for a colour", code),
+            SchemeError::Opposites(ref a, ref b) => write!(f, "{} and {} cannot be on opposite faces", a, b),
+        }
+    }
+}
+
+impl Error for SchemeError {}
+
+/// Pairs of colours that are on opposite faces in the Western or the
+/// Japanese scheme.
+const STANDARD_OPPOSITES: [(&str, &str); 5] = [
+    ("white", "yellow"),
+    ("white", "blue"),
+    ("red", "orange"),
+    ("blue", "green"),
+    ("yellow", "green"),
+];
+
+impl ColorScheme {
+    /// The usual scheme, also called BOY as blue, orange and yellow go
+    /// clockwise round their corner. White on top, green in front.
+    pub fn western() -> ColorScheme {
+        ColorScheme {
+            name: String::from("Western"),
+            colours: [
+                Colour::new("white", 0xFF_FF_FF, 'W'),
+                Colour::new("red", 0xB7_12_34, 'R'),
+                Colour::new("green", 0x00_9B_48, 'G'),
+                Colour::new("yellow", 0xFF_D5_00, 'Y'),
+                Colour::new("orange", 0xFF_58_00, 'O'),
+                Colour::new("blue", 0x00_46_AD, 'B'),
+            ],
+        }
+    }
+
+    /// The Japanese scheme, with blue opposite white and yellow opposite
+    /// green.
+    pub fn japanese() -> ColorScheme {
+        ColorScheme {
+            name: String::from("Japanese"),
+            colours: [
+                Colour::new("white", 0xFF_FF_FF, 'W'),
+                Colour::new("red", 0xB7_12_34, 'R'),
+                Colour::new("green", 0x00_9B_48, 'G'),
+                Colour::new("blue", 0x00_46_AD, 'B'),
+                Colour::new("orange", 0xFF_58_00, 'O'),
+                Colour::new("yellow", 0xFF_D5_00, 'Y'),
+            ],
+        }
+    }
+
+    /// The Western layout in the Okabe-Ito colours, which stay apart for
+    /// the common kinds of colour blindness. Red and orange become
+    /// vermillion and reddish purple.
+    pub fn colour_blind() -> ColorScheme {
+        ColorScheme {
+            name: String::from("Colour-blind"),
+            colours: [
+                Colour::new("white", 0xFF_FF_FF, 'W'),
+                Colour::new("vermillion", 0xD5_5E_00, 'V'),
+                Colour::new("bluish green", 0x00_9E_73, 'G'),
+                Colour::new("yellow", 0xF0_E4_42, 'Y'),
+                Colour::new("reddish purple", 0xCC_79_A7, 'P'),
+                Colour::new("blue", 0x00_72_B2, 'B'),
+            ],
+        }
+    }
+
+    /// Every built in scheme.
+    pub fn presets() -> Vec<ColorScheme> {
+        vec![ColorScheme::western(), ColorScheme::japanese(), ColorScheme::colour_blind()]
+    }
+
+    /// Finds a built in scheme by its name, or otherwise reads the scheme
+    /// file at `name`.
+    ///
+    /// # Parameters
+    /// * `name` - The name of a preset, in any case, or a path.
+    /// # Returns
+    /// * `Result<ColorScheme, SchemeError>` - The scheme, or why it could not
+    ///     be loaded.
+    pub fn load(name: &str) -> Result<ColorScheme, SchemeError> {
+        match ColorScheme::presets()
+            .into_iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+        {
+            Some(scheme) => Ok(scheme),
+            None => ColorScheme::from_file(name),
+        }
+    }
+
+    /// The scheme every front end uses: `$RUBIKS_COLOUR_SCHEME` if set,
+    /// which may be a preset or a file, otherwise the Western scheme.
+    pub fn configured() -> Result<ColorScheme, SchemeError> {
+        match env::var("RUBIKS_COLOUR_SCHEME") {
+            Ok(name) => ColorScheme::load(&name),
+            Err(_) => Ok(ColorScheme::western()),
+        }
+    }
+
+    /// Reads and checks a scheme file.
+    ///
+    /// # Parameters
+    /// * `path` - Where the file is.
+    /// # Returns
+    /// * `Result<ColorScheme, SchemeError>` - The scheme, or why it could not
+    ///     be used.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ColorScheme, SchemeError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| SchemeError::Io(format!("cannot read {}: {}", path.display(), e)))?;
+        text.parse()
+    }
+
+    /// The colour of a face.
+    pub fn colour(&self, face: Facelets) -> &Colour {
+        &self.colours[face as usize]
+    }
+
+    /// Checks the scheme could be a real cube. Every colour needs its own
+    /// name and letter, and colours that are never opposite each other on
+    /// a real cube must not be put on opposite faces.
+    ///
+    /// # Returns
+    /// * `Result<(), SchemeError>` - The first problem found, if any.
+    pub fn check(&self) -> Result<(), SchemeError> {
+        for (i, colour) in self.colours.iter().enumerate() {
+            if colour.code.is_whitespace() || colour.code.is_control() {
+                return Err(SchemeError::BadCode(colour.code));
+            }
+            for other in self.colours[..i].iter() {
+                if other.name.eq_ignore_ascii_case(&colour.name) {
+                    return Err(SchemeError::SameName(colour.name.clone()));
+                }
+                if other.code.eq_ignore_ascii_case(&colour.code) {
+                    return Err(SchemeError::SameCode(colour.code));
+                }
+            }
+        }
+
+        // Only colours with the usual names can be checked, as nothing is
+        // known about any others.
+        let standard = |name: &str| {
+            STANDARD_OPPOSITES
+                .iter()
+                .any(|(a, b)| a.eq_ignore_ascii_case(name) || b.eq_ignore_ascii_case(name))
+        };
+        for (a, b) in [(Facelets::U, Facelets::D), (Facelets::R, Facelets::L), (Facelets::F, Facelets::B)].iter() {
+            let (a, b) = (&self.colour(*a).name, &self.colour(*b).name);
+            if standard(a)
+                && standard(b)
+                && !STANDARD_OPPOSITES.iter().any(|(x, y)| {
+                    (x.eq_ignore_ascii_case(a) && y.eq_ignore_ascii_case(b))
+                        || (x.eq_ignore_ascii_case(b) && y.eq_ignore_ascii_case(a))
+                })
+            {
+                return Err(SchemeError::Opposites(a.clone(), b.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a cube typed in the letters of this scheme. Which colour is
+    /// which face comes from the centres, as in `IFace::from_symbols`, so
+    /// the cube may be held any way up.
+    ///
+    /// # Parameters
+    /// * `s` - The letter of each facelet, in the order U, R, F, D, L, B.
+    /// # Returns
+    /// * `Result<Face, FaceError>` - The face, or the first thing wrong with
+    ///     `s`.
+    pub fn parse(&self, s: &str) -> Result<Face, FaceError> {
+        for (i, c) in s.chars().enumerate() {
+            if !self.colours.iter().any(|colour| colour.code.eq_ignore_ascii_case(&c)) {
+                return Err(FaceError::UnknownSymbol {
+                    position: i,
+                    character: c,
+                });
+            }
+        }
+        Face::from_symbols(s)
+    }
+
+    /// Writes a cube in the letters of this scheme, the inverse of `parse`
+    /// for a cube held the usual way up.
+    ///
+    /// # Parameters
+    /// * `face` - The `Face` to write.
+    /// # Returns
+    /// * `String` - 54 letters.
+    pub fn render(&self, face: &Face) -> String {
+        face.iter().map(|f| self.colour(*f).code).collect()
+    }
+}
+
+impl fmt::Display for ColorScheme {
+    /// Writes the scheme in the same form as a scheme file.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "name = {}", self.name)?;
+        for (face, colour) in FACELET_LIST.iter().zip(self.colours.iter()) {
+            writeln!(
+                f,
+                "{:?} = {} #{:02X}{:02X}{:02X} {}",
+                face, colour.name, colour.rgb[0], colour.rgb[1], colour.rgb[2], colour.code
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = SchemeError;
+
+    /// Reads a scheme in the form of a scheme file, and checks it.
+    fn from_str(s: &str) -> Result<ColorScheme, SchemeError> {
+        let mut name = String::from("Custom");
+        let mut colours: [Option<Colour>; 6] = Default::default();
+
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax = || SchemeError::Syntax {
+                line: n + 1,
+                text: line.to_string(),
+            };
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(syntax()),
+            };
+            if key.eq_ignore_ascii_case("name") {
+                name = value.to_string();
+                continue;
+            }
+
+            let face = match FACELET_LIST
+                .iter()
+                .find(|f| format!("{:?}", f).eq_ignore_ascii_case(key))
+            {
+                Some(face) => *face,
+                None => return Err(syntax()),
+            };
+            let words: Vec<&str> = value.split_whitespace().collect();
+            if words.len() < 3 {
+                return Err(syntax());
+            }
+            let mut code = words[words.len() - 1].chars();
+            let code = match (code.next(), code.next()) {
+                (Some(c), None) => c,
+                _ => return Err(syntax()),
+            };
+            let hex = words[words.len() - 2];
+            if !hex.starts_with('#') || hex.len() != 7 {
+                return Err(syntax());
+            }
+            let rgb = u32::from_str_radix(&hex[1..], 16).map_err(|_| syntax())?;
+
+            if colours[face as usize].is_some() {
+                return Err(SchemeError::RepeatedFace(face));
+            }
+            colours[face as usize] = Some(Colour::new(&words[..words.len() - 2].join(" "), rgb, code));
+        }
+
+        for face in FACELET_LIST.iter() {
+            if colours[*face as usize].is_none() {
+                return Err(SchemeError::MissingFace(*face));
+            }
+        }
+        let [u, r, f, d, l, b] = colours;
+        let scheme = ColorScheme {
+            name,
+            colours: [u.unwrap(), r.unwrap(), f.unwrap(), d.unwrap(), l.unwrap(), b.unwrap()],
+        };
+        scheme.check()?;
+        Ok(scheme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use physical::Cube;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_presets() {
+        for scheme in ColorScheme::presets().iter() {
+            assert_eq!(scheme.check(), Ok(()), "{}", scheme.name);
+            assert_eq!(scheme.to_string().parse::<ColorScheme>().as_ref(), Ok(scheme));
+            assert_eq!(ColorScheme::load(&scheme.name.to_uppercase()).as_ref(), Ok(scheme));
+
+            let mut rng = StdRng::seed_from_u64(25);
+            let face = Face::from_cube(&Cube::random(&mut rng));
+            let typed = scheme.render(&face);
+            assert_eq!(scheme.parse(&typed), Ok(face));
+            assert_eq!(scheme.parse(&typed.to_lowercase()), Ok(face));
+        }
+        assert_eq!(ColorScheme::western().colour(Facelets::F).name, "green");
+        assert_eq!(ColorScheme::western().colour(Facelets::U).rgba(), [1.0; 4]);
+        assert_eq!(
+            ColorScheme::western().parse(&"U".repeat(54)),
+            Err(FaceError::UnknownSymbol {
+                position: 0,
+                character: 'U'
+            })
+        );
+    }
+
+    #[test]
+    fn test_scheme_files() {
+        let text = "# A scheme with its faces out of order.\n\
+                    name = Stickerless\n\
+                    D = lemon yellow #F5E50A y\n\
+                    u = white #ffffff w\n\
+                    R = red #E00000 r\n\
+                    F = green #00C000 g\n\
+                    L = orange #FF8000 o\n\
+                    B = blue #0050FF b\n";
+        let scheme: ColorScheme = text.parse().unwrap();
+        assert_eq!(scheme.name, "Stickerless");
+        assert_eq!(scheme.colour(Facelets::D).name, "lemon yellow");
+        assert_eq!(scheme.colour(Facelets::D).rgb, [0xF5, 0xE5, 0x0A]);
+        assert_eq!(scheme.colour(Facelets::U).code, 'w');
+
+        assert_eq!(
+            "U = white #FFFFFF".parse::<ColorScheme>(),
+            Err(SchemeError::Syntax {
+                line: 1,
+                text: String::from("U = white #FFFFFF")
+            })
+        );
+        let western = ColorScheme::western().to_string();
+        assert_eq!(
+            western.replace("B = blue #0046AD B", "").parse::<ColorScheme>(),
+            Err(SchemeError::MissingFace(Facelets::B))
+        );
+        assert_eq!(
+            western.replace("#0046AD B", "#0046AD W").parse::<ColorScheme>(),
+            Err(SchemeError::SameCode('W'))
+        );
+        // White is never opposite green on a real cube.
+        assert_eq!(
+            western
+                .replace("F = green", "F = yellow")
+                .replace("D = yellow", "D = green")
+                .parse::<ColorScheme>(),
+            Err(SchemeError::Opposites(String::from("white"), String::from("green")))
+        );
+        assert!(ColorScheme::from_file("/nonexistent/scheme").is_err());
+    }
+}
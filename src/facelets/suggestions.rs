@@ -0,0 +1,333 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Suggests the nearest cubes that can be solved when one that was typed in
+//! cannot.
+//!
+//! If every piece is there, a bad twist, flip or parity is fixed by
+//! twisting a corner, flipping an edge or swapping two pieces of the cube
+//! `turn_into_cube` gives. Otherwise one or two stickers are given another
+//! colour. Either way the suggestions that change the fewest facelets come
+//! first, as the fewer stickers were read wrong the more likely it is.
+
+use std::fmt;
+
+use facelets::validation::ValidationReport;
+use facelets::{Face, Facelets, IFace, CORNER_LIST, EDGE_LIST};
+use physical::corner_cubies::Corner;
+use physical::edge_cubies::Edge;
+
+/// One change to the cube that was typed in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Correction {
+    /// Twist the corner in a position, 1 for clockwise and 2 for
+    /// anticlockwise.
+    TwistCorner { position: Corner, twist: u8 },
+    /// Flip the edge in a position.
+    FlipEdge { position: Edge },
+    /// Swap the corners in two positions.
+    SwapCorners(Corner, Corner),
+    /// Swap the edges in two positions.
+    SwapEdges(Edge, Edge),
+    /// Give a facelet another colour.
+    Recolour {
+        facelet: usize,
+        from: Facelets,
+        to: Facelets,
+    },
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Correction::TwistCorner { position, twist } => write!(
+                f,
+                "twist the {:?} corner {}",
+                position,
+                if twist == 1 { "clockwise" } else { "anticlockwise" }
+            ),
+            Correction::FlipEdge { position } => write!(f, "flip the {:?} edge", position),
+            Correction::SwapCorners(a, b) => write!(f, "swap the {:?} and {:?} corners", a, b),
+            Correction::SwapEdges(a, b) => write!(f, "swap the {:?} and {:?} edges", a, b),
+            Correction::Recolour { facelet, from, to } => write!(
+                f,
+                "change {}{} from {:?} to {:?}",
+                ["U", "R", "F", "D", "L", "B"][facelet / 9],
+                facelet % 9 + 1,
+                from,
+                to
+            ),
+        }
+    }
+}
+
+/// A way to make a cube that can be solved.
+///
+/// # Variables
+/// * `corrections` - The changes to make.
+/// * `face` - The cube once they are made.
+/// * `changed` - The facelets that are different afterwards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub corrections: Vec<Correction>,
+    pub face: Face,
+    pub changed: Vec<usize>,
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, correction) in self.corrections.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", then ")?;
+            }
+            write!(f, "{}", correction)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the cubes closest to `face` that can be solved.
+///
+/// # Parameters
+/// * `face` - A `Face` that cannot be solved.
+/// * `limit` - The most suggestions to give.
+/// # Returns
+/// * `Vec<Suggestion>` - The suggestions, most likely first. Empty if
+///     `face` can already be solved or nothing close enough was found.
+pub fn suggest(face: &Face, limit: usize) -> Vec<Suggestion> {
+    let report = face.validate();
+    if report.is_solvable() {
+        return Vec::new();
+    }
+
+    let mut suggestions = if report.problems.iter().all(|p| p.code() >= 4) {
+        fix_pieces(face)
+    } else {
+        fix_stickers(face, &report)
+    };
+    // A stable sort, so equally likely suggestions keep to the order of
+    // the positions.
+    suggestions.sort_by_key(|s| (s.changed.len(), s.corrections.len()));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Every combination of one swap, one flip and one twist that fixes the
+/// parity, flip and twist of a face whose pieces are all there.
+fn fix_pieces(face: &Face) -> Vec<Suggestion> {
+    let rubiks = face.turn_into_cube();
+
+    let mut swaps = vec![None];
+    if rubiks.corner_parity() != rubiks.edge_parity() {
+        swaps.clear();
+        for a in 0..8 {
+            for b in a + 1..8 {
+                swaps.push(Some(Correction::SwapCorners(CORNER_LIST[a], CORNER_LIST[b])));
+            }
+        }
+        for a in 0..12 {
+            for b in a + 1..12 {
+                swaps.push(Some(Correction::SwapEdges(EDGE_LIST[a], EDGE_LIST[b])));
+            }
+        }
+    }
+    let mut flips = vec![None];
+    if rubiks.edges.orientation.iter().sum::<u8>() % 2 != 0 {
+        flips = EDGE_LIST.iter().map(|e| Some(Correction::FlipEdge { position: *e })).collect();
+    }
+    let mut twists = vec![None];
+    let twist = rubiks.corners.orientation.iter().sum::<u8>() % 3;
+    if twist != 0 {
+        twists = CORNER_LIST
+            .iter()
+            .map(|c| {
+                Some(Correction::TwistCorner {
+                    position: *c,
+                    twist: 3 - twist,
+                })
+            })
+            .collect();
+    }
+
+    let mut suggestions = Vec::new();
+    for swap in swaps.iter() {
+        for flip in flips.iter() {
+            for twist in twists.iter() {
+                let corrections: Vec<Correction> = [*swap, *flip, *twist].iter().filter_map(|c| *c).collect();
+                let mut c = rubiks;
+                for correction in corrections.iter() {
+                    match *correction {
+                        Correction::TwistCorner { position, twist } => {
+                            let o = &mut c.corners.orientation[position as usize];
+                            *o = (*o + twist) % 3;
+                        }
+                        Correction::FlipEdge { position } => {
+                            c.edges.orientation[position as usize] = 1 - c.edges.orientation[position as usize];
+                        }
+                        Correction::SwapCorners(a, b) => {
+                            c.corners.permutation.swap(a as usize, b as usize);
+                            c.corners.orientation.swap(a as usize, b as usize);
+                        }
+                        Correction::SwapEdges(a, b) => {
+                            c.edges.permutation.swap(a as usize, b as usize);
+                            c.edges.orientation.swap(a as usize, b as usize);
+                        }
+                        Correction::Recolour { .. } => {}
+                    }
+                }
+                suggestions.push(suggestion(face, corrections, Face::from_cube(&c)));
+            }
+        }
+    }
+    suggestions
+}
+
+/// Every way of recolouring one facelet, or two of the facelets with a
+/// problem, that gives a face which can be solved. Centres are left alone,
+/// as they decide which colour is which face.
+fn fix_stickers(face: &Face, report: &ValidationReport) -> Vec<Suggestion> {
+    let stickers: Vec<usize> = (0..54).filter(|i| i % 9 != 4).collect();
+    let suspects: Vec<usize> = report.facelets().into_iter().filter(|i| i % 9 != 4).collect();
+
+    let mut suggestions = Vec::new();
+    for i in stickers.iter() {
+        for colour in COLOURS.iter() {
+            if *colour == face[*i] {
+                continue;
+            }
+            let mut fixed = *face;
+            fixed[*i] = *colour;
+            if fixed.validate().is_solvable() {
+                let corrections = vec![recolour(face, *i, *colour)];
+                suggestions.push(suggestion(face, corrections, fixed));
+            }
+        }
+    }
+    for (n, i) in suspects.iter().enumerate() {
+        for j in suspects[n + 1..].iter() {
+            for first in COLOURS.iter() {
+                for second in COLOURS.iter() {
+                    if *first == face[*i] || *second == face[*j] {
+                        continue;
+                    }
+                    let mut fixed = *face;
+                    fixed[*i] = *first;
+                    fixed[*j] = *second;
+                    if fixed.validate().is_solvable() {
+                        let corrections = vec![recolour(face, *i, *first), recolour(face, *j, *second)];
+                        suggestions.push(suggestion(face, corrections, fixed));
+                    }
+                }
+            }
+        }
+    }
+    suggestions
+}
+
+fn recolour(face: &Face, facelet: usize, to: Facelets) -> Correction {
+    Correction::Recolour {
+        facelet,
+        from: face[facelet],
+        to,
+    }
+}
+
+fn suggestion(face: &Face, corrections: Vec<Correction>, fixed: Face) -> Suggestion {
+    Suggestion {
+        corrections,
+        changed: (0..54).filter(|i| face[*i] != fixed[*i]).collect(),
+        face: fixed,
+    }
+}
+
+const COLOURS: [Facelets; 6] = [
+    Facelets::U,
+    Facelets::R,
+    Facelets::F,
+    Facelets::D,
+    Facelets::L,
+    Facelets::B,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use physical::Cube;
+
+    fn check(face: &Face, best: Correction, changed: usize) {
+        let suggestions = suggest(face, 20);
+        assert!(!suggestions.is_empty());
+        assert_eq!(suggestions[0].changed.len(), changed);
+        assert!(suggestions
+            .iter()
+            .take_while(|s| s.changed.len() == changed)
+            .any(|s| s.corrections == vec![best]));
+        for s in suggestions.iter() {
+            assert!(s.face.validate().is_solvable(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_solvable() {
+        assert!(suggest(&Face::new_clean(), 10).is_empty());
+    }
+
+    #[test]
+    fn test_fix_pieces() {
+        let mut c = Cube::new();
+        c.r();
+        c.u();
+        c.corners.orientation[Corner::DLF as usize] = (c.corners.orientation[Corner::DLF as usize] + 1) % 3;
+        check(
+            &Face::from_cube(&c),
+            Correction::TwistCorner {
+                position: Corner::DLF,
+                twist: 2,
+            },
+            3,
+        );
+
+        let mut c = Cube::new();
+        c.edges.orientation[Edge::BL as usize] = 1;
+        check(&Face::from_cube(&c), Correction::FlipEdge { position: Edge::BL }, 2);
+
+        // Swapping two edges of the upper face back only changes their side
+        // stickers.
+        let mut c = Cube::new();
+        c.edges.permutation.swap(0, 1);
+        let suggestions = suggest(&Face::from_cube(&c), 10);
+        assert_eq!(suggestions[0].corrections, vec![Correction::SwapEdges(Edge::UR, Edge::UF)]);
+        assert_eq!(suggestions[0].face, Face::new_clean());
+        assert_eq!(suggestions[0].changed, vec![10, 19]);
+    }
+
+    #[test]
+    fn test_fix_stickers() {
+        let mut face = Face::new_clean();
+        face[19] = Facelets::R;
+        check(
+            &face,
+            Correction::Recolour {
+                facelet: 19,
+                from: Facelets::R,
+                to: Facelets::F,
+            },
+            1,
+        );
+        assert_eq!(suggest(&face, 10)[0].to_string(), "change F2 from R to F");
+
+        // Two stickers typed in the wrong order.
+        let mut face = Face::new_clean();
+        face.swap(1, 19);
+        let suggestions = suggest(&face, 10);
+        assert_eq!(suggestions[0].face, Face::new_clean());
+        assert_eq!(suggestions[0].changed, vec![1, 19]);
+    }
+}
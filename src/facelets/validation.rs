@@ -0,0 +1,408 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Finds everything that stops a `Face` from being a real cube.
+//!
+//! Unlike `IFace::check_if_can_be_solved` this does not stop at the first
+//! problem, and each problem names the facelets involved so they can be
+//! pointed out to whoever typed them in.
+
+use std::fmt;
+
+use facelets::{corner_colours, edge_colours, Face, Facelets, CORNER_INDEXES, CORNER_LIST, EDGE_INDEXES, EDGE_LIST};
+use physical::corner_cubies::Corner;
+use physical::edge_cubies::Edge;
+use physical::Cube;
+
+/// One thing wrong with a `Face`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A colour that is not on exactly 9 facelets, and the facelets it is on.
+    ColourCount {
+        colour: Facelets,
+        count: usize,
+        facelets: Vec<usize>,
+    },
+    /// Three stickers in a corner position that no corner has, such as two
+    /// of the same colour, opposite colours or a mirror image of a corner.
+    ImpossibleCorner { facelets: [usize; 3], colours: [Facelets; 3] },
+    /// A corner that is in more than one position, and the facelets of all
+    /// of them.
+    DuplicateCorner { corner: Corner, facelets: Vec<usize> },
+    /// A corner that is not on the cube at all.
+    MissingCorner { corner: Corner },
+    /// Two stickers in an edge position that no edge has.
+    ImpossibleEdge { facelets: [usize; 2], colours: [Facelets; 2] },
+    /// An edge that is in more than one position, and the facelets of all
+    /// of them.
+    DuplicateEdge { edge: Edge, facelets: Vec<usize> },
+    /// An edge that is not on the cube at all.
+    MissingEdge { edge: Edge },
+    /// The corners and edges are permuted with different parities, 0 for
+    /// even and 1 for odd.
+    Parity { corners: i32, edges: i32 },
+    /// The edge flips add up to an odd number, and the facelets of every
+    /// flipped edge.
+    Flip { total: u8, facelets: Vec<usize> },
+    /// The corner twists do not add up to a multiple of 3. `total` is what
+    /// is left over, and the facelets are those of every twisted corner.
+    Twist { total: u8, facelets: Vec<usize> },
+}
+
+impl Problem {
+    /// The facelets involved in the problem. Empty for a missing piece or a
+    /// parity problem, as no one facelet is at fault.
+    ///
+    /// # Returns
+    /// * `Vec<usize>` - Indexes into the `Face`, between 0 and 53.
+    pub fn facelets(&self) -> Vec<usize> {
+        match *self {
+            Problem::ColourCount { ref facelets, .. }
+            | Problem::DuplicateCorner { ref facelets, .. }
+            | Problem::DuplicateEdge { ref facelets, .. }
+            | Problem::Flip { ref facelets, .. }
+            | Problem::Twist { ref facelets, .. } => facelets.clone(),
+            Problem::ImpossibleCorner { facelets, .. } => facelets.to_vec(),
+            Problem::ImpossibleEdge { facelets, .. } => facelets.to_vec(),
+            Problem::MissingCorner { .. } | Problem::MissingEdge { .. } | Problem::Parity { .. } => Vec::new(),
+        }
+    }
+
+    /// The code `IFace::check_if_can_be_solved` gives for this problem.
+    ///
+    /// # Returns
+    /// * `usize` - Between 1 and 6.
+    pub fn code(&self) -> usize {
+        match *self {
+            Problem::ColourCount { .. } => 1,
+            Problem::ImpossibleEdge { .. } | Problem::DuplicateEdge { .. } | Problem::MissingEdge { .. } => 2,
+            Problem::ImpossibleCorner { .. } | Problem::DuplicateCorner { .. } | Problem::MissingCorner { .. } => 3,
+            Problem::Parity { .. } => 4,
+            Problem::Flip { .. } => 5,
+            Problem::Twist { .. } => 6,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Problem::ColourCount { colour, count, .. } => {
+                write!(f, "there are {} {:?} facelets instead of 9", count, colour)
+            }
+            Problem::ImpossibleCorner { facelets, colours } => write!(
+                f,
+                "facelets {}, {} and {} are coloured {:?}, {:?} and {:?}, which is not a corner",
+                facelets[0], facelets[1], facelets[2], colours[0], colours[1], colours[2]
+            ),
+            Problem::DuplicateCorner { corner, ref facelets } => {
+                write!(f, "the {:?} corner is there {} times", corner, facelets.len() / 3)
+            }
+            Problem::MissingCorner { corner } => write!(f, "the {:?} corner is missing", corner),
+            Problem::ImpossibleEdge { facelets, colours } => write!(
+                f,
+                "facelets {} and {} are coloured {:?} and {:?}, which is not an edge",
+                facelets[0], facelets[1], colours[0], colours[1]
+            ),
+            Problem::DuplicateEdge { edge, ref facelets } => {
+                write!(f, "the {:?} edge is there {} times", edge, facelets.len() / 2)
+            }
+            Problem::MissingEdge { edge } => write!(f, "the {:?} edge is missing", edge),
+            Problem::Parity { corners, edges } => write!(
+                f,
+                "the corner parity is {} but the edge parity is {}",
+                corners, edges
+            ),
+            Problem::Flip { total, .. } => write!(f, "the edge flips add up to {} instead of 0 (mod 2)", total),
+            Problem::Twist { total, .. } => {
+                write!(f, "the corner twists add up to {} instead of 0 (mod 3)", total)
+            }
+        }
+    }
+}
+
+/// Every problem found with a `Face`.
+///
+/// The parity, flip and twist can only be worked out once every piece is
+/// there exactly once, so they are only checked when there are no problems
+/// with the colours or pieces.
+///
+/// # Variables
+/// * `problems` - The problems, colours first, then corners, edges, parity,
+///     flip and twist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    /// Checks every facelet of `face`.
+    ///
+    /// # Parameters
+    /// * `face` - The `Face` to check.
+    /// # Returns
+    /// * `ValidationReport` - Everything wrong with `face`.
+    pub fn new(face: &Face) -> ValidationReport {
+        let mut problems = Vec::new();
+
+        for colour in [
+            Facelets::U,
+            Facelets::R,
+            Facelets::F,
+            Facelets::D,
+            Facelets::L,
+            Facelets::B,
+        ]
+        .iter()
+        {
+            let facelets: Vec<usize> = (0..54).filter(|i| face[*i] == *colour).collect();
+            if facelets.len() != 9 {
+                problems.push(Problem::ColourCount {
+                    colour: *colour,
+                    count: facelets.len(),
+                    facelets,
+                });
+            }
+        }
+
+        let mut corner_places: Vec<Vec<usize>> = vec![Vec::new(); 8];
+        for (i, fac) in CORNER_INDEXES.iter().enumerate() {
+            match read_corner(face, i) {
+                Some((corner, _)) => corner_places[corner as usize].push(i),
+                None => problems.push(Problem::ImpossibleCorner {
+                    facelets: *fac,
+                    colours: [face[fac[0]], face[fac[1]], face[fac[2]]],
+                }),
+            }
+        }
+        for (corner, places) in CORNER_LIST.iter().zip(corner_places.iter()) {
+            match places.len() {
+                0 => problems.push(Problem::MissingCorner { corner: *corner }),
+                1 => {}
+                _ => problems.push(Problem::DuplicateCorner {
+                    corner: *corner,
+                    facelets: places.iter().flat_map(|i| CORNER_INDEXES[*i].to_vec()).collect(),
+                }),
+            }
+        }
+
+        let mut edge_places: Vec<Vec<usize>> = vec![Vec::new(); 12];
+        for (i, fac) in EDGE_INDEXES.iter().enumerate() {
+            match read_edge(face, i) {
+                Some((edge, _)) => edge_places[edge as usize].push(i),
+                None => problems.push(Problem::ImpossibleEdge {
+                    facelets: *fac,
+                    colours: [face[fac[0]], face[fac[1]]],
+                }),
+            }
+        }
+        for (edge, places) in EDGE_LIST.iter().zip(edge_places.iter()) {
+            match places.len() {
+                0 => problems.push(Problem::MissingEdge { edge: *edge }),
+                1 => {}
+                _ => problems.push(Problem::DuplicateEdge {
+                    edge: *edge,
+                    facelets: places.iter().flat_map(|i| EDGE_INDEXES[*i].to_vec()).collect(),
+                }),
+            }
+        }
+
+        if problems.is_empty() {
+            let mut c = Cube::new();
+            for i in 0..8 {
+                let (corner, o) = read_corner(face, i).unwrap();
+                c.corners.permutation[i] = corner;
+                c.corners.orientation[i] = o;
+            }
+            for i in 0..12 {
+                let (edge, o) = read_edge(face, i).unwrap();
+                c.edges.permutation[i] = edge;
+                c.edges.orientation[i] = o;
+            }
+
+            if c.corner_parity() != c.edge_parity() {
+                problems.push(Problem::Parity {
+                    corners: c.corner_parity(),
+                    edges: c.edge_parity(),
+                });
+            }
+            let flip = c.edges.orientation.iter().sum::<u8>() % 2;
+            if flip != 0 {
+                problems.push(Problem::Flip {
+                    total: flip,
+                    facelets: (0..12)
+                        .filter(|i| c.edges.orientation[*i] != 0)
+                        .flat_map(|i| EDGE_INDEXES[i].to_vec())
+                        .collect(),
+                });
+            }
+            let twist = c.corners.orientation.iter().sum::<u8>() % 3;
+            if twist != 0 {
+                problems.push(Problem::Twist {
+                    total: twist,
+                    facelets: (0..8)
+                        .filter(|i| c.corners.orientation[*i] != 0)
+                        .flat_map(|i| CORNER_INDEXES[i].to_vec())
+                        .collect(),
+                });
+            }
+        }
+
+        ValidationReport { problems }
+    }
+
+    /// Whether nothing is wrong, so the `Face` can be solved.
+    pub fn is_solvable(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Every facelet involved in any of the problems, each once and in
+    /// order.
+    ///
+    /// # Returns
+    /// * `Vec<usize>` - Indexes into the `Face`, between 0 and 53.
+    pub fn facelets(&self) -> Vec<usize> {
+        let mut facelets: Vec<usize> = self.problems.iter().flat_map(|p| p.facelets()).collect();
+        facelets.sort();
+        facelets.dedup();
+        facelets
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    /// Writes one problem on each line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the corner in corner position `i` of `face`, and its twist.
+///
+/// # Returns
+/// * `Option<(Corner, u8)>` - The corner, or None if no corner has those
+///     colours in that order.
+fn read_corner(face: &Face, i: usize) -> Option<(Corner, u8)> {
+    let fac = CORNER_INDEXES[i];
+    for corner in CORNER_LIST.iter() {
+        let colours = corner_colours(*corner);
+        for o in 0..3 {
+            if (0..3).all(|k| face[fac[(k + o) % 3]] == colours[k]) {
+                return Some((*corner, o as u8));
+            }
+        }
+    }
+    None
+}
+
+/// Finds the edge in edge position `i` of `face`, and its flip.
+///
+/// # Returns
+/// * `Option<(Edge, u8)>` - The edge, or None if no edge has those colours.
+fn read_edge(face: &Face, i: usize) -> Option<(Edge, u8)> {
+    let fac = EDGE_INDEXES[i];
+    for edge in EDGE_LIST.iter() {
+        let colours = edge_colours(*edge);
+        for o in 0..2 {
+            if (0..2).all(|k| face[fac[(k + o) % 2]] == colours[k]) {
+                return Some((*edge, o as u8));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use facelets::IFace;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_valid() {
+        let mut rng = StdRng::seed_from_u64(22);
+        for _ in 0..1000 {
+            let face = Face::from_cube(&Cube::random(&mut rng));
+            let report = ValidationReport::new(&face);
+            assert!(report.is_solvable(), "{}", report);
+            assert_eq!(face.check_if_can_be_solved(), 0);
+        }
+    }
+
+    #[test]
+    fn test_pieces() {
+        // Two stickers of the URF corner swapped make a mirror image.
+        let mut face = Face::new_clean();
+        face.swap(8, 9);
+        let report = ValidationReport::new(&face);
+        assert_eq!(
+            report.problems,
+            vec![
+                Problem::ImpossibleCorner {
+                    facelets: [8, 9, 20],
+                    colours: [Facelets::R, Facelets::U, Facelets::F],
+                },
+                Problem::MissingCorner { corner: Corner::URF },
+            ]
+        );
+        assert_eq!(report.facelets(), vec![8, 9, 20]);
+        assert_eq!(face.check_if_can_be_solved(), 3);
+
+        // The UR edge stickers copied onto the DB edge. The last edges used
+        // to go unchecked.
+        let mut face = Face::new_clean();
+        face[34] = Facelets::U;
+        face[52] = Facelets::R;
+        let report = ValidationReport::new(&face);
+        assert!(report.problems.contains(&Problem::DuplicateEdge {
+            edge: Edge::UR,
+            facelets: vec![5, 10, 34, 52],
+        }));
+        assert!(report.problems.contains(&Problem::MissingEdge { edge: Edge::DB }));
+        assert!(report.problems.contains(&Problem::ColourCount {
+            colour: Facelets::D,
+            count: 8,
+            facelets: vec![27, 28, 29, 30, 31, 32, 33, 35],
+        }));
+        assert!(!face.check_edges_colours());
+        assert_eq!(face.check_if_can_be_solved(), 1);
+    }
+
+    #[test]
+    fn test_orientations() {
+        let mut c = Cube::new();
+        c.edges.orientation[0] = 1;
+        c.corners.orientation[1] = 2;
+        c.corners.permutation.swap(6, 7);
+        let face = Face::from_cube(&c);
+        let report = ValidationReport::new(&face);
+        assert_eq!(
+            report.problems,
+            vec![
+                Problem::Parity { corners: 1, edges: 0 },
+                Problem::Flip {
+                    total: 1,
+                    facelets: vec![5, 10],
+                },
+                Problem::Twist {
+                    total: 2,
+                    facelets: vec![6, 18, 38],
+                },
+            ]
+        );
+        assert_eq!(face.check_if_can_be_solved(), 4);
+    }
+}
@@ -12,14 +12,20 @@
 //! to Dear ImGui, an immediate mode gui library.
 
 
+use std::sync::mpsc;
+use std::thread;
+
 use imgui::*;
 
 use crate::facelets;
+use facelets::scheme::ColorScheme;
+use facelets::suggestions::{self, Suggestion};
 use facelets::IFace;
 use crate::solver;
 use crate::ui_support;
 
 pub struct State {
+    scheme: ColorScheme,
     colors: [Color; 6],
     current: Color,
     // Rubiks cube array.
@@ -28,129 +34,124 @@ pub struct State {
     // 5
     rubiks: [Color; 54],
     notify_text: &'static str,
+    // What the solver is up to, or the last solution found.
+    solver_text: String,
+    solving: Option<Solving>,
+    // Solvable cubes close to the one entered, when it cannot be solved.
+    suggestions: Vec<Suggestion>,
 }
 
-#[derive(PartialEq, Copy, Clone, Debug)]
-pub enum Color {
-    White,
-    Red,
-    Blue,
-    Orange,
-    Green,
-    Yellow
+/// A search running on its own thread, so the window keeps drawing while it
+/// works.
+struct Solving {
+    cancel: solver::CancelToken,
+    updates: mpsc::Receiver<SolverUpdate>,
 }
-impl Color {
-    fn get_vec(&self) -> [f32; 4] {
-        match self {
-            Self::White  => [1.0, 1.0, 1.0, 1.0],
-            Self::Red    => [1.0, 0.0, 0.0, 1.0],
-            Self::Blue   => [0.0, 0.0, 1.0, 1.0],
-            Self::Orange => [1.0, 0.64, 0.0, 1.0],
-            Self::Green  => [0.0, 1.0, 0.0, 1.0],
-            Self::Yellow => [1.0, 1.0, 0.0, 1.0],
-            
-        }
-    }
-    // @@TODO:: 
-    #[allow(dead_code)]
-    fn get_facelet(&self) -> facelets::Facelets{
-        match self {
-            Self::White  => facelets::Facelets::U,
-            Self::Red    => facelets::Facelets::L,
-            Self::Blue   => facelets::Facelets::F,
-            Self::Orange => facelets::Facelets::R,
-            Self::Green  => facelets::Facelets::B,
-            Self::Yellow => facelets::Facelets::D,
-        }        
-    }
-    fn get_char(&self) -> char{
-        match self {
-            Self::White  => 'u',
-            Self::Red    => 'l',
-            Self::Blue   => 'f',
-            Self::Orange => 'r',
-            Self::Green  => 'b',
-            Self::Yellow => 'd',
-        }        
+
+enum SolverUpdate {
+    Progress(solver::Progress),
+    Done(Result<solver::SolveResult, solver::SolveError>),
+}
+
+impl Solving {
+    fn start(cube: crate::physical::Cube) -> Solving {
+        let cancel = solver::CancelToken::new();
+        let (sender, updates) = mpsc::channel();
+        let progress_sender = sender.clone();
+        let context = solver::SolverContext::new()
+            .with_cancel_token(cancel.clone())
+            .with_progress(move |progress| {
+                // The window may have gone, and nobody is listening.
+                let _ = progress_sender.send(SolverUpdate::Progress(*progress));
+            });
+        thread::spawn(move || {
+            let result = solver::complete_search_with_context(&cube, context);
+            let _ = sender.send(SolverUpdate::Done(result));
+        });
+        Solving { cancel, updates }
     }
 }
 
-fn convert_color_rubiks_to_chars(rubiks: [Color; 54]) -> facelets::RubiksChar{
+/// A colour of the scheme, named by the face it is on when the cube is
+/// solved. How it looks and the letter for it come from `State::scheme`.
+pub type Color = facelets::Facelets;
+
+fn convert_color_rubiks_to_chars(rubiks: [Color; 54], scheme: &ColorScheme) -> facelets::RubiksChar{
     let mut a: facelets::RubiksChar = [' '; 54];
     // Remap the way they are in the gui to the old order required for the algo.
     // Upper
     for i in 0..9{
-        a[i] = rubiks[i].get_char();
+        a[i] = scheme.colour(rubiks[i]).code;
     }
     // Left
     for i in 9..18{
-        a[i + 3 * 9] = rubiks[i].get_char();
+        a[i + 3 * 9] = scheme.colour(rubiks[i]).code;
     }
     // Front
     for i in 18..27{
-        a[i] = rubiks[i].get_char();
+        a[i] = scheme.colour(rubiks[i]).code;
     }
     // Right
     for i in 27..36{
-        a[i - 9 * 2] = rubiks[i].get_char();
+        a[i - 9 * 2] = scheme.colour(rubiks[i]).code;
     }
     // Back
     for i in 36..45{
-        a[i + 9] = rubiks[i].get_char();
+        a[i + 9] = scheme.colour(rubiks[i]).code;
     }
     // Down
     for i in 45..54{
-        a[i - 9 * 2] = rubiks[i].get_char();
+        a[i - 9 * 2] = scheme.colour(rubiks[i]).code;
     }
     return a;
 }
 
-#[allow(dead_code)]
-fn convert_color_rubiks_to_facelets(rubiks: [Color; 54]) -> facelets::Face{
-    let mut a: facelets::Face = [facelets::Facelets::U; 54];
-    // Remap the way they are in the gui to the old order required for the algo.
-    // Upper
-    for i in 0..9{
-        // 0 - 8
-        a[i] = rubiks[i].get_facelet();
-    }
-    // Left
-    for i in 9..18{
-        a[i + 3 * 9] = rubiks[i].get_facelet();
-    }
-    // Front
-    for i in 18..27{
-        a[i] = rubiks[i].get_facelet();
-    }
-    // Right
-    for i in 27..36{
-        a[i - 9 * 2] = rubiks[i].get_facelet();
-    }
-    // Back
-    for i in 36..45{
-        a[i + 9] = rubiks[i].get_facelet();
+/// Colours a `Face` in the order the gui draws it, giving each face the
+/// colour of its centre in `rubiks`.
+fn convert_facelets_to_color_rubiks(face: &facelets::Face, rubiks: [Color; 54]) -> [Color; 54] {
+    // Where each facelet the gui draws is in a `Face`. The gui goes upper,
+    // left, front, right, back and down.
+    let facelet_index = |i: usize| match i / 9 {
+        1 => i + 3 * 9,
+        3 | 5 => i - 9 * 2,
+        4 => i + 9,
+        _ => i,
+    };
+    let mut centres = [Color::U; 6];
+    for i in 0..54 {
+        if facelet_index(i) % 9 == 4 {
+            centres[facelet_index(i) / 9] = rubiks[i];
+        }
     }
-    // Down
-    for i in 45..54{
-        a[i - 9 * 2] = rubiks[i].get_facelet();
+    let mut a = [Color::U; 54];
+    for i in 0..54 {
+        a[i] = centres[face[facelet_index(i)] as usize];
     }
-
-    return a;
+    a
 }
 
 pub fn create_window(){
+    let scheme = ColorScheme::configured().unwrap_or_else(|e| {
+        println!("{}; using the Western colours instead.", e);
+        ColorScheme::western()
+    });
     let mut state = State{
+        scheme,
+        // In the order the faces are drawn.
         colors: [
-            Color::White,
-            Color::Red,
-            Color::Blue,
-            Color::Orange,
-            Color::Green,
-            Color::Yellow,
+            Color::U,
+            Color::L,
+            Color::F,
+            Color::R,
+            Color::B,
+            Color::D,
         ],
-        current: Color::White,
-        rubiks: [Color::White; 54],
+        current: Color::U,
+        rubiks: [Color::U; 54],
         notify_text: "",
+        solver_text: String::new(),
+        solving: None,
+        suggestions: Vec::new(),
     };
 
     for i in 0..6{
@@ -180,12 +181,13 @@ macro_rules! ig_make_label {
 fn row_buttons(ui: &Ui, width: i32, row: i32, state: &mut State){
     for x in 0..width{
         if ColorButton::new(ig_make_label!("Rubiks", (row + x).to_string()),
-                            state.rubiks[(row + x) as usize].get_vec())
+                            state.scheme.colour(state.rubiks[(row + x) as usize]).rgba())
             .size([30.0,30.0])
             .tooltip(false)
             .build(ui){
                 state.rubiks[(row + x) as usize] = state.current;
                 state.notify_text = "Facelet Clicked";
+                state.suggestions.clear();
             }
         ui.same_line_with_spacing(0.0, 5.0);
     }
@@ -218,8 +220,8 @@ pub fn rubiks_cube_flat(ui: &Ui, state: &mut State) {
         ui.text(state.notify_text);
 
         // Set colour.
-        for i in 0..5 {
-            if ColorButton::new(ig_make_label!("Selector", i.to_string()), state.colors[i].get_vec())
+        for i in 0..6 {
+            if ColorButton::new(ig_make_label!("Selector", i.to_string()), state.scheme.colour(state.colors[i]).rgba())
                 .size([30.0,30.0])
                 .tooltip(false)
                 .build(ui){
@@ -247,18 +249,74 @@ pub fn rubiks_cube_flat(ui: &Ui, state: &mut State) {
         block_buttons(&ui, 3, 3, 5, state);
         ui.new_line();
 
-        if ui.button(im_str!("Solve!"), [90.0, 30.0]) {
-            let r = convert_color_rubiks_to_chars(state.rubiks).iter().cloned().collect::<String>();
-            let face = facelets::Face::new(&r);
-            print!("{:?}", face);
-            let (a, b) = face.return_code_matcher();
-            state.notify_text = a;
-            if b {
-                let mut cube = face.turn_into_cube();
-                let moves = solver::complete_search(&mut cube);
-                //state.notify_text = &moves;
-                print!("{}", moves);
+        poll_solver(state);
+        if state.solving.is_some() {
+            if ui.button(im_str!("Cancel"), [90.0, 30.0]) {
+                if let Some(ref solving) = state.solving {
+                    solving.cancel.cancel();
+                }
+            }
+        } else if ui.button(im_str!("Solve!"), [90.0, 30.0]) {
+            let r = convert_color_rubiks_to_chars(state.rubiks, &state.scheme).iter().cloned().collect::<String>();
+            match state.scheme.parse(&r) {
+                Ok(face) => {
+                    print!("{:?}", face);
+                    let (a, b) = face.return_code_matcher();
+                    state.notify_text = a;
+                    state.suggestions = suggestions::suggest(&face, 5);
+                    if b {
+                        let cube = face.turn_into_cube();
+                        state.solving = Some(Solving::start(cube));
+                        state.solver_text = String::from("Solving...");
+                    }
+                }
+                Err(e) => state.solver_text = e.to_string(),
+            }
+        }
+        ui.text(&state.solver_text);
+
+        // One click puts the suggested cube in place of the one entered.
+        if !state.suggestions.is_empty() {
+            ui.text("Did you mean to:");
+            let mut chosen = None;
+            for (i, suggestion) in state.suggestions.iter().enumerate() {
+                if ui.button(ig_dynamic_str!(format!("{}##Suggestion{}", suggestion, i)), [0.0, 0.0]) {
+                    chosen = Some(suggestion.face);
+                }
+            }
+            if let Some(face) = chosen {
+                state.rubiks = convert_facelets_to_color_rubiks(&face, state.rubiks);
+                state.suggestions.clear();
+                state.notify_text = "Suggestion applied.";
             }
         }
     });
 }
+
+/// Picks up whatever the solver thread has sent since the last frame.
+fn poll_solver(state: &mut State) {
+    let mut done = None;
+    if let Some(ref solving) = state.solving {
+        for update in solving.updates.try_iter() {
+            match update {
+                SolverUpdate::Progress(progress) => {
+                    state.solver_text = format!(
+                        "Phase {} at depth {}: {} positions in {:.1}s",
+                        progress.phase,
+                        progress.depth,
+                        progress.nodes,
+                        progress.elapsed.as_secs_f32()
+                    );
+                }
+                SolverUpdate::Done(result) => done = Some(result),
+            }
+        }
+    }
+    if let Some(result) = done {
+        state.solver_text = match result {
+            Ok(result) => format!("Move list: {}", result),
+            Err(e) => e.to_string(),
+        };
+        state.solving = None;
+    }
+}
@@ -16,6 +16,8 @@ extern crate glium;
 extern crate imgui_glium_renderer;
 extern crate imgui_winit_support;
 extern crate clipboard;
+extern crate memmap2;
+extern crate rand;
 
 mod facelets;
 mod physical;
@@ -28,6 +30,13 @@ mod ui_support;
 mod utility;
     
 fn main() {
+    match solver::table_file::CacheSettings::from_args(std::env::args().skip(1)) {
+        Ok(settings) => solver::table_file::configure(settings),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
     gui::create_window();
     //console::create_terminal();
 }
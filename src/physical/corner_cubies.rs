@@ -16,8 +16,9 @@
 /// A numbered enum of the corner pieces.
 ///
 /// It is numbered to make ordered operations for permutation calculations,
-/// easier to compute. The order is important.
-#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
+/// easier to compute. The order is important. Each is stored in one byte.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[repr(u8)]
 pub enum Corner {
     URF = 0,
     UFL,
@@ -29,13 +30,39 @@ pub enum Corner {
     DRB,
 }
 
-/// The main CornerCubie struct.
+/// Every `Corner` in order, for going from an index back to a `Corner`.
+pub const CORNERS: [Corner; 8] = [
+    Corner::URF,
+    Corner::UFL,
+    Corner::ULB,
+    Corner::UBR,
+    Corner::DFR,
+    Corner::DLF,
+    Corner::DBL,
+    Corner::DRB,
+];
+
+/// The corners of a cube, as two flat arrays indexed by position.
+///
+/// # Variables
+/// * `permutation` - The `Corner` sitting in each position.
+/// * `orientation` - A value of 0, 1, and 2 for each position, where 0 is
+///     the default orientation, 1 a clockwise twist, and 2 an
+///     anti-clockwise twist.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct CornerCubies {
+    pub permutation: [Corner; 8],
+    pub orientation: [u8; 8],
+}
+
+/// A single corner cubie, as given by `Cube::corner_cubies`.
 ///
 /// # Variables
 /// * `orientation` - A value of 0, 1, and 2, where 0 is the default
 ///     orientation, 1 a clockwise twist, and 2 an anti-clockwise twist.
-/// * `coordinate` - A `Corner` that represents the cubes current position.
-/// * `old_coordiante` - The `coordinate` that was last held before a move.
+/// * `coordinate` - The `Corner` in this cubie's position.
+/// * `old_coordinate` - Always the same as `coordinate`.
+#[deprecated(note = "corners are kept in `CornerCubies`; use `Cube::corners`")]
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct CornerCubie {
     pub orientation: i32,
@@ -43,6 +70,7 @@ pub struct CornerCubie {
     pub old_coordinate: Corner,
 }
 
+#[allow(deprecated)]
 impl CornerCubie {
     /// Creates a new `CornerCubie` with coordinate c.
     ///
@@ -51,95 +79,121 @@ impl CornerCubie {
     /// # Return
     /// * `CornerCubie`
     pub fn new(c: Corner) -> CornerCubie {
-        let a = CornerCubie {
+        CornerCubie {
             orientation: 0,
             coordinate: c,
             old_coordinate: c,
-        };
-
-        a
+        }
     }
+}
 
-    /// A generic movement function.
-    ///
-    /// # Parameters
-    /// * `corners` - A reference of what each corner should become with this
-    ///     movement.
-    /// * `orientation_change` - An array of 8 `i32` types, each relating to
-    ///     the additional orientation change.
-    fn movement(&mut self, corners: &[Corner; 8], orientation_change: &[i32; 8]) {
-        self.old_coordinate = self.coordinate;
-        match self.coordinate {
-            Corner::URF => {
-                self.coordinate = corners[0];
-                self.orientation = (self.orientation + orientation_change[0]) % 3;
-            }
-            Corner::UFL => {
-                self.coordinate = corners[1];
-                self.orientation = (self.orientation + orientation_change[1]) % 3;
-            }
-            Corner::ULB => {
-                self.coordinate = corners[2];
-                self.orientation = (self.orientation + orientation_change[2]) % 3;
-            }
-            Corner::UBR => {
-                self.coordinate = corners[3];
-                self.orientation = (self.orientation + orientation_change[3]) % 3;
-            }
-            Corner::DFR => {
-                self.coordinate = corners[4];
-                self.orientation = (self.orientation + orientation_change[4]) % 3;
-            }
-            Corner::DLF => {
-                self.coordinate = corners[5];
-                self.orientation = (self.orientation + orientation_change[5]) % 3;
-            }
-            Corner::DBL => {
-                self.coordinate = corners[6];
-                self.orientation = (self.orientation + orientation_change[6]) % 3;
+/// The corners of a solved cube.
+pub const SOLVED: CornerCubies = CornerCubies {
+    permutation: CORNERS,
+    orientation: [0; 8],
+};
+
+/// The corners after one clockwise move of each face from solved, in the
+/// order F, B, U, D, L, R. Doing a move is multiplying by one of these.
+pub const MOVES: [CornerCubies; 6] = [
+    CornerCubies {
+        permutation: F_CORNER_TRANSFORM,
+        orientation: F_CORNER_ORIENTATION_TRANSFORM,
+    },
+    CornerCubies {
+        permutation: B_CORNER_TRANSFORM,
+        orientation: B_CORNER_ORIENTATION_TRANSFORM,
+    },
+    CornerCubies {
+        permutation: U_CORNER_TRANSFORM,
+        orientation: U_CORNER_ORIENTATION_TRANSFORM,
+    },
+    CornerCubies {
+        permutation: D_CORNER_TRANSFORM,
+        orientation: D_CORNER_ORIENTATION_TRANSFORM,
+    },
+    CornerCubies {
+        permutation: L_CORNER_TRANSFORM,
+        orientation: L_CORNER_ORIENTATION_TRANSFORM,
+    },
+    CornerCubies {
+        permutation: R_CORNER_TRANSFORM,
+        orientation: R_CORNER_ORIENTATION_TRANSFORM,
+    },
+];
+
+/// Multiplies two sets of corners, as in doing `a` and then `b`.
+///
+/// The moves are written in Kociemba's "is replaced by" form, so after the
+/// product each position holds the corner that `a` had in the position
+/// `b` names for it.
+///
+/// Mirror images of a cube, as used by the cube symmetries, have their
+/// corner orientations stored as 3 to 5 instead of 0 to 2. Further
+/// explanation at (http://kociemba.org/math/cubegroup.htm)
+///
+/// # Parameters
+/// * `a` - The corners of the first cube.
+/// * `b` - The corners of the cube to multiply by.
+/// # Return
+/// * `CornerCubies` - The corners of the product.
+pub fn multiply(a: &CornerCubies, b: &CornerCubies) -> CornerCubies {
+    let mut product = *a;
+    for i in 0..8 {
+        let from = b.permutation[i] as usize;
+        let (ori_a, ori_b) = (a.orientation[from], b.orientation[i]);
+        let orientation = if ori_a < 3 && ori_b < 3 {
+            (ori_a + ori_b) % 3
+        } else if ori_a < 3 {
+            // Only b is mirrored.
+            let o = ori_a + ori_b;
+            if o >= 6 {
+                o - 3
+            } else {
+                o
             }
-            Corner::DRB => {
-                self.coordinate = corners[7];
-                self.orientation = (self.orientation + orientation_change[7]) % 3;
+        } else if ori_b < 3 {
+            // Only a is mirrored.
+            let o = ori_a - ori_b;
+            if o < 3 {
+                o + 3
+            } else {
+                o
             }
+        } else {
+            // Both are mirrored, which cancels out.
+            (ori_a + 3 - ori_b) % 3
         };
-    }
-
-    /// Typical rubiks cube movements.
 
-    /// A forward clockwise movement.
-    pub fn f(&mut self) {
-        self.movement(&F_CORNER_TRANSFORM, &F_CORNER_ORIENTATION_TRANSFORM)
-    }
-
-    /// A back clockwise movement.
-    pub fn b(&mut self) {
-        self.movement(&B_CORNER_TRANSFORM, &B_CORNER_ORIENTATION_TRANSFORM)
-    }
-
-    /// A right clockwise movement.
-    pub fn r(&mut self) {
-        self.movement(&R_CORNER_TRANSFORM, &R_CORNER_ORIENTATION_TRANSFORM)
-    }
-
-    /// A left clockwise movement.
-    pub fn l(&mut self) {
-        self.movement(&L_CORNER_TRANSFORM, &L_CORNER_ORIENTATION_TRANSFORM)
-    }
-
-    /// A upper clockwise movement.
-    pub fn u(&mut self) {
-        self.movement(&U_CORNER_TRANSFORM, &U_CORNER_ORIENTATION_TRANSFORM)
+        product.permutation[i] = a.permutation[from];
+        product.orientation[i] = orientation;
     }
+    product
+}
 
-    /// A down clockwise movement.
-    pub fn d(&mut self) {
-        self.movement(&D_CORNER_TRANSFORM, &D_CORNER_ORIENTATION_TRANSFORM)
+/// Inverts a set of corners, so that multiplying by the result undoes them.
+///
+/// # Parameters
+/// * `a` - The corners to invert.
+/// # Return
+/// * `CornerCubies` - The corners of the inverse.
+pub fn inverse(a: &CornerCubies) -> CornerCubies {
+    let mut inverse = *a;
+    for i in 0..8 {
+        let to = a.permutation[i] as usize;
+        inverse.permutation[to] = CORNERS[i];
+        // Mirrored orientations are their own inverse.
+        inverse.orientation[to] = if a.orientation[i] >= 3 {
+            a.orientation[i]
+        } else {
+            (3 - a.orientation[i]) % 3
+        };
     }
+    inverse
 }
 
 /// ***************************************************************************
-/// The variables used in the generic `movement` function above. These are
+/// The moves used to build `MOVES` above. These are
 /// static as they'll be called a lot and there is no reason to create them
 /// each time instead of referencing these values.
 ///
@@ -156,7 +210,7 @@ const F_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DBL,
     Corner::DRB,
 ];
-const F_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [1, 2, 0, 0, 2, 1, 0, 0];
+const F_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [1, 2, 0, 0, 2, 1, 0, 0];
 
 const B_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::URF,
@@ -168,7 +222,7 @@ const B_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::ULB,
     Corner::DBL,
 ];
-const B_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [0, 0, 1, 2, 0, 0, 2, 1];
+const B_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [0, 0, 1, 2, 0, 0, 2, 1];
 
 const R_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DFR,
@@ -180,7 +234,7 @@ const R_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DBL,
     Corner::UBR,
 ];
-const R_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [2, 0, 0, 1, 1, 0, 0, 2];
+const R_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [2, 0, 0, 1, 1, 0, 0, 2];
 
 const L_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::URF,
@@ -192,7 +246,7 @@ const L_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DLF,
     Corner::DRB,
 ];
-const L_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [0, 1, 2, 0, 0, 2, 1, 0];
+const L_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [0, 1, 2, 0, 0, 2, 1, 0];
 
 const U_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::UBR,
@@ -204,7 +258,7 @@ const U_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DBL,
     Corner::DRB,
 ];
-const U_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
+const U_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
 
 const D_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::URF,
@@ -216,4 +270,4 @@ const D_CORNER_TRANSFORM: [Corner; 8] = [
     Corner::DRB,
     Corner::DFR,
 ];
-const D_CORNER_ORIENTATION_TRANSFORM: [i32; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
+const D_CORNER_ORIENTATION_TRANSFORM: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
@@ -13,13 +13,12 @@
 //! Deals with movements and how they shift the coordinates and orientation
 //! of the edge cubie in question.
 
-use std::cmp::Ordering;
-
 /// A numbered enum of the edge pieces.
 ///
 /// It is numbered to make ordered operations for permutation calculations,
-/// easier to compute. The order is important.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+/// easier to compute. The order is important. Each is stored in one byte.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[repr(u8)]
 pub enum Edge {
     UR = 0,
     UF,
@@ -35,38 +34,48 @@ pub enum Edge {
     BR,
 }
 
-/// The main edge cubie.
+/// Every `Edge` in order, for going from an index back to an `Edge`.
+pub const EDGES: [Edge; 12] = [
+    Edge::UR,
+    Edge::UF,
+    Edge::UL,
+    Edge::UB,
+    Edge::DR,
+    Edge::DF,
+    Edge::DL,
+    Edge::DB,
+    Edge::FR,
+    Edge::FL,
+    Edge::BL,
+    Edge::BR,
+];
+
+/// The edges of a cube, as two flat arrays indexed by position.
+///
+/// # Variables
+/// * `permutation` - The `Edge` sitting in each position.
+/// * `orientation` - 0 or 1 for each position, where 1 is a flipped edge.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct EdgeCubies {
+    pub permutation: [Edge; 12],
+    pub orientation: [u8; 12],
+}
+
+/// A single edge cubie, as given by `Cube::edge_cubies`.
 ///
 /// # Variables
-/// * `orientation` - A value of 0, 1, and 2, where 0 is the default
-///     orientation, 1 a clockwise twist, and 2 an anti-clockwise twist.
-/// * `coordinate` - A `Edge` that represents the cubes current position.
-/// * `old_coordiante` - The `coordinate` that was last held before a move.
-#[derive(Debug, Eq, Copy, Clone)]
+/// * `orientation` - 0, or 1 for a flipped edge.
+/// * `coordinate` - The `Edge` in this cubie's position.
+/// * `old_coordinate` - Always the same as `coordinate`.
+#[deprecated(note = "edges are kept in `EdgeCubies`; use `Cube::edges`")]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub struct EdgeCubie {
     pub orientation: i32,
     pub coordinate: Edge,
     pub old_coordinate: Edge,
 }
 
-impl Ord for EdgeCubie {
-    fn cmp(&self, other: &EdgeCubie) -> Ordering {
-        (self.coordinate as i32).cmp(&(other.coordinate as i32))
-    }
-}
-
-impl PartialOrd for EdgeCubie {
-    fn partial_cmp(&self, other: &EdgeCubie) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl PartialEq for EdgeCubie {
-    fn eq(&self, other: &EdgeCubie) -> bool {
-        (self.coordinate as i32) == (other.coordinate as i32)
-    }
-}
-
+#[allow(deprecated)]
 impl EdgeCubie {
     /// Creates a new `EdgeCubie` with coordinate e.
     ///
@@ -75,110 +84,85 @@ impl EdgeCubie {
     /// # Return
     /// * `EdgeCubie`
     pub fn new(e: Edge) -> EdgeCubie {
-        let a = EdgeCubie {
+        EdgeCubie {
             orientation: 0,
             coordinate: e,
             old_coordinate: e,
-        };
-
-        a
-    }
-
-    /// A generic movement function.
-    ///
-    /// # Parameters
-    /// * `edges` - A reference of what each edge should become with this
-    ///     movement.
-    /// * `orientation_change` - An array of 8 `i32` types, each relating to
-    ///     the additional orientation change.
-    fn movement(&mut self, edges: &[Edge; 12], orientation_change: &[i32; 12]) {
-        self.old_coordinate = self.coordinate;
-        match self.coordinate {
-            Edge::UR => {
-                self.coordinate = edges[0];
-                self.orientation = (self.orientation + orientation_change[0]) % 2;
-            }
-            Edge::UF => {
-                self.coordinate = edges[1];
-                self.orientation = (self.orientation + orientation_change[1]) % 2;
-            }
-            Edge::UL => {
-                self.coordinate = edges[2];
-                self.orientation = (self.orientation + orientation_change[2]) % 2;
-            }
-            Edge::UB => {
-                self.coordinate = edges[3];
-                self.orientation = (self.orientation + orientation_change[3]) % 2;
-            }
-            Edge::DR => {
-                self.coordinate = edges[4];
-                self.orientation = (self.orientation + orientation_change[4]) % 2;
-            }
-            Edge::DF => {
-                self.coordinate = edges[5];
-                self.orientation = (self.orientation + orientation_change[5]) % 2;
-            }
-            Edge::DL => {
-                self.coordinate = edges[6];
-                self.orientation = (self.orientation + orientation_change[6]) % 2;
-            }
-            Edge::DB => {
-                self.coordinate = edges[7];
-                self.orientation = (self.orientation + orientation_change[7]) % 2;
-            }
-            Edge::FR => {
-                self.coordinate = edges[8];
-                self.orientation = (self.orientation + orientation_change[8]) % 2;
-            }
-            Edge::FL => {
-                self.coordinate = edges[9];
-                self.orientation = (self.orientation + orientation_change[9]) % 2;
-            }
-            Edge::BL => {
-                self.coordinate = edges[10];
-                self.orientation = (self.orientation + orientation_change[10]) % 2;
-            }
-            Edge::BR => {
-                self.coordinate = edges[11];
-                self.orientation = (self.orientation + orientation_change[11]) % 2;
-            }
         }
     }
-    /// Typical rubiks cube movements.
-
-    /// A forward clockwise movement.
-    pub fn f(&mut self) {
-        self.movement(&F_EDGE_TRANSFORM, &F_EDGE_ORIENTATION_TRANSFORM)
-    }
-
-    /// A back clockwise movement.
-    pub fn b(&mut self) {
-        self.movement(&B_EDGE_TRANSFORM, &B_EDGE_ORIENTATION_TRANSFORM)
-    }
+}
 
-    /// A right clockwise movement.
-    pub fn r(&mut self) {
-        self.movement(&R_EDGE_TRANSFORM, &R_EDGE_ORIENTATION_TRANSFORM)
-    }
+/// The edges of a solved cube.
+pub const SOLVED: EdgeCubies = EdgeCubies {
+    permutation: EDGES,
+    orientation: [0; 12],
+};
 
-    /// A left clockwise movement.
-    pub fn l(&mut self) {
-        self.movement(&L_EDGE_TRANSFORM, &L_EDGE_ORIENTATION_TRANSFORM)
-    }
+/// The edges after one clockwise move of each face from solved, in the
+/// order F, B, U, D, L, R. Doing a move is multiplying by one of these.
+pub const MOVES: [EdgeCubies; 6] = [
+    EdgeCubies {
+        permutation: F_EDGE_TRANSFORM,
+        orientation: F_EDGE_ORIENTATION_TRANSFORM,
+    },
+    EdgeCubies {
+        permutation: B_EDGE_TRANSFORM,
+        orientation: B_EDGE_ORIENTATION_TRANSFORM,
+    },
+    EdgeCubies {
+        permutation: U_EDGE_TRANSFORM,
+        orientation: U_EDGE_ORIENTATION_TRANSFORM,
+    },
+    EdgeCubies {
+        permutation: D_EDGE_TRANSFORM,
+        orientation: D_EDGE_ORIENTATION_TRANSFORM,
+    },
+    EdgeCubies {
+        permutation: L_EDGE_TRANSFORM,
+        orientation: L_EDGE_ORIENTATION_TRANSFORM,
+    },
+    EdgeCubies {
+        permutation: R_EDGE_TRANSFORM,
+        orientation: R_EDGE_ORIENTATION_TRANSFORM,
+    },
+];
 
-    /// A upper clockwise movement.
-    pub fn u(&mut self) {
-        self.movement(&U_EDGE_TRANSFORM, &U_EDGE_ORIENTATION_TRANSFORM)
+/// Multiplies two sets of edges, as in doing `a` and then `b`. Works the
+/// same way as the corner version.
+///
+/// # Parameters
+/// * `a` - The edges of the first cube.
+/// * `b` - The edges of the cube to multiply by.
+/// # Return
+/// * `EdgeCubies` - The edges of the product.
+pub fn multiply(a: &EdgeCubies, b: &EdgeCubies) -> EdgeCubies {
+    let mut product = *a;
+    for i in 0..12 {
+        let from = b.permutation[i] as usize;
+        product.permutation[i] = a.permutation[from];
+        product.orientation[i] = (a.orientation[from] + b.orientation[i]) % 2;
     }
+    product
+}
 
-    /// A down clockwise movement.
-    pub fn d(&mut self) {
-        self.movement(&D_EDGE_TRANSFORM, &D_EDGE_ORIENTATION_TRANSFORM)
+/// Inverts a set of edges, so that multiplying by the result undoes them.
+///
+/// # Parameters
+/// * `a` - The edges to invert.
+/// # Return
+/// * `EdgeCubies` - The edges of the inverse.
+pub fn inverse(a: &EdgeCubies) -> EdgeCubies {
+    let mut inverse = *a;
+    for i in 0..12 {
+        let to = a.permutation[i] as usize;
+        inverse.permutation[to] = EDGES[i];
+        inverse.orientation[to] = a.orientation[i];
     }
+    inverse
 }
 
 /// ***************************************************************************
-/// The variables used in the generic `movement` function above. These are
+/// The moves used to build `MOVES` above. These are
 /// static as they'll be called a lot and there is no reason to create them
 /// each time instead of referencing these values.
 ///
@@ -199,7 +183,7 @@ const F_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::BL,
     Edge::BR,
 ];
-const F_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0];
+const F_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0];
 
 const B_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::UR,
@@ -215,7 +199,7 @@ const B_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::UB,
     Edge::DB,
 ];
-const B_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1];
+const B_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1];
 
 const R_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::FR,
@@ -231,7 +215,7 @@ const R_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::BL,
     Edge::UR,
 ];
-const R_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+const R_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
 
 const L_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::UR,
@@ -247,7 +231,7 @@ const L_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::DL,
     Edge::BR,
 ];
-const L_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+const L_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
 
 const U_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::UB,
@@ -263,7 +247,7 @@ const U_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::BL,
     Edge::BR,
 ];
-const U_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+const U_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
 
 const D_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::UR,
@@ -279,4 +263,4 @@ const D_EDGE_TRANSFORM: [Edge; 12] = [
     Edge::BL,
     Edge::BR,
 ];
-const D_EDGE_ORIENTATION_TRANSFORM: [i32; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+const D_EDGE_ORIENTATION_TRANSFORM: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
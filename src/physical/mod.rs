@@ -14,9 +14,46 @@
 //! transformation are in a group/coordinate style that best works with
 //! the two-phase algorithm.
 
+use std::fmt;
+use std::sync::OnceLock;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use physical::corner_cubies::CornerCubies;
+use physical::edge_cubies::{Edge, EdgeCubies};
 use utility;
 pub mod corner_cubies;
 pub mod edge_cubies;
+pub mod rank;
+pub mod symmetry;
+
+/// n! for every n the coordinates need.
+const FACTORIAL: [i32; 12] = [1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 39916800];
+
+/// n choose k for every n and k the UD slice coordinates need, as the
+/// slice is read off a cube at every step of the optimal search.
+const BINOMIAL: [[i32; 5]; 12] = [
+    [1, 0, 0, 0, 0],
+    [1, 1, 0, 0, 0],
+    [1, 2, 1, 0, 0],
+    [1, 3, 3, 1, 0],
+    [1, 4, 6, 4, 1],
+    [1, 5, 10, 10, 5],
+    [1, 6, 15, 20, 15],
+    [1, 7, 21, 35, 35],
+    [1, 8, 28, 56, 70],
+    [1, 9, 36, 84, 126],
+    [1, 10, 45, 120, 210],
+    [1, 11, 55, 165, 330],
+];
+
+/// The number of ways to choose `k` of `n` things, for the small `k` the UD
+/// slice coordinates need.
+#[inline]
+fn binomial(n: i32, k: i32) -> i32 {
+    BINOMIAL[n as usize][k as usize]
+}
 
 /// The main struct of the program.
 ///
@@ -24,35 +61,99 @@ pub mod edge_cubies;
 /// style notation. This is such that it will be best optimised when used with
 /// the two-phase algorithm as designed by [Kociemba](http://kociemba.org).
 ///
+/// The cube is only its cubies, kept as flat arrays of bytes, so a move is a
+/// few table lookups. The coordinates the solver uses are not stored;
+/// methods such as `corner_orientation` work them out from the cubies when
+/// asked for. `corner_cubies` and `edge_cubies` give the cubies as
+/// `CornerCubie` and `EdgeCubie` values.
+///
 /// # Variables
 ///
-/// * `corner_orientation` - A value between 0 and 2186, representing the
-///     orientation of the corners overall.
-/// * `edge_orientation` - A value between 0 and 2047, representing the
-///     orientation of the edges overall.
-/// * `corner_permutation` - A value between 0 and 40319, representing the
-///     permutation of the cubes corners.
-/// * `phase_two_edge_permutaion` - A value between 0 and 40320, but between
-///     0 and 24 for a G1 state `Cube` the permutation of the cubes edges, only
-///     valid in phase 2..
-/// * `corner_parity` - The parity of the corner permutation.
-/// * `edge_parity` - The parity of the edge permutation.
-/// * `ud_slice` - A value between 0 and 494, representing the front UD
-///     slice edges.
-/// * `corners` - An array of the 8 `CornerCubies`.
-/// * `edges` - An array of the 12 `EdgeCubies`.
-#[derive(Debug, Clone, Copy)]
+/// * `corners` - Which `Corner` is in each corner position, and its twist.
+/// * `edges` - Which `Edge` is in each edge position, and its flip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Cube {
-    pub corner_orientation: i32,
-    pub edge_orientation: i32,
-    pub corner_permutation: i32,
-    pub phase_two_edge_permutation: i32,
-    pub corner_parity: i32,
-    pub edge_parity: i32,
-    pub ud_slice: i32,
-    pub ud_sorted_slice: i32,
-    pub corners: [corner_cubies::CornerCubie; 8],
-    pub edges: [edge_cubies::EdgeCubie; 12],
+    pub corners: CornerCubies,
+    pub edges: EdgeCubies,
+}
+
+/// Why a `Cube` cannot be solved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unsolvable {
+    /// A cubie is missing, and another is there twice.
+    MissingCubies,
+    /// The corner twist does not add up to a whole turn.
+    CornerTwist,
+    /// An odd number of edges are flipped.
+    EdgeFlip,
+    /// The corner and edge permutations have different parities, as if two
+    /// pieces had been swapped.
+    Parity,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match *self {
+            Unsolvable::MissingCubies => "not every cubie is on the cube exactly once",
+            Unsolvable::CornerTwist => "the total corner twist is wrong",
+            Unsolvable::EdgeFlip => "the total edge flip is wrong",
+            Unsolvable::Parity => "the edge and corner parities are not equal",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// The cubes one clockwise turn away from solved, in the order of
+/// `solver::Moves`: F, B, U, D, L and R. Doing a move is multiplying by one
+/// of these.
+///
+/// # Return
+/// * `&'static [Cube; 6]`
+pub fn basic_moves() -> &'static [Cube; 6] {
+    &BASIC_MOVES
+}
+
+static BASIC_MOVES: [Cube; 6] = [
+    Cube {
+        corners: corner_cubies::MOVES[0],
+        edges: edge_cubies::MOVES[0],
+    },
+    Cube {
+        corners: corner_cubies::MOVES[1],
+        edges: edge_cubies::MOVES[1],
+    },
+    Cube {
+        corners: corner_cubies::MOVES[2],
+        edges: edge_cubies::MOVES[2],
+    },
+    Cube {
+        corners: corner_cubies::MOVES[3],
+        edges: edge_cubies::MOVES[3],
+    },
+    Cube {
+        corners: corner_cubies::MOVES[4],
+        edges: edge_cubies::MOVES[4],
+    },
+    Cube {
+        corners: corner_cubies::MOVES[5],
+        edges: edge_cubies::MOVES[5],
+    },
+];
+
+/// Every quarter, half and anti-clockwise turn of each face as a cube, in
+/// the order of `solver::Moves` from F1 to R3.
+///
+/// # Return
+/// * `&'static [Cube; 18]`
+pub fn face_turns() -> &'static [Cube; 18] {
+    static FACE_TURNS: OnceLock<[Cube; 18]> = OnceLock::new();
+    FACE_TURNS.get_or_init(|| {
+        let mut turns = [Cube::new(); 18];
+        for i in 0..18 {
+            turns[i] = BASIC_MOVES[i / 3].pow(i as i32 % 3 + 1);
+        }
+        turns
+    })
 }
 
 impl Cube {
@@ -60,320 +161,699 @@ impl Cube {
     /// # Return
     /// * `Cube`
     pub fn new() -> Cube {
-        let mut new_cube = Cube {
-            corner_orientation: 0,
-            edge_orientation: 0,
-            corner_permutation: 0,
-            phase_two_edge_permutation: 0,
-            corner_parity: 0,
-            edge_parity: 0,
-            ud_slice: 0,
-            ud_sorted_slice: 0,
-            corners: [
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::URF),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::UFL),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::ULB),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::UBR),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::DFR),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::DLF),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::DBL),
-                corner_cubies::CornerCubie::new(corner_cubies::Corner::DRB),
-            ],
-            edges: [
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::UR),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::UF),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::UL),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::UB),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::DR),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::DF),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::DL),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::DB),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::FR),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::FL),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::BL),
-                edge_cubies::EdgeCubie::new(edge_cubies::Edge::BR),
-            ],
-        };
-        new_cube.coordinate_adjustments();
-        new_cube
-    }
-
-    #[allow(dead_code)]
-    pub fn calculate_orientations_init(&mut self) {
-        for (i, c) in self.corners.iter_mut().enumerate() {
-            c.orientation = (((i as i32 - c.coordinate as i32) % 3) + 3) % 3;
-        }
-        for (i, e) in self.edges.iter_mut().enumerate() {
-            e.orientation = (((i as i32 - e.coordinate as i32) % 4) + 4) % 4;
+        Cube {
+            corners: corner_cubies::SOLVED,
+            edges: edge_cubies::SOLVED,
         }
     }
 
-    /// Calculates the corner orientation.
+    /// The corner orientation.
     ///
-    /// Should be called after every movement. Calculates a tenary value used
-    /// to represent the corner orientation of the whole cube.  Further
-    /// explanation at (http://kociemba.org/math/coordlevel.htm)
-    pub fn calculate_corner_orientation(&mut self) {
+    /// A tenary value used to represent the corner orientation of the whole
+    /// cube, between 0 and 2186. The last corner is left out as its twist is
+    /// fixed by the other seven. Further explanation at
+    /// (http://kociemba.org/math/coordlevel.htm)
+    pub fn corner_orientation(&self) -> i32 {
         let mut sum = 0;
         for i in 0..7 {
-            sum = sum + self.corners[i].orientation * 3_i32.pow((6 - i) as u32)
+            sum = 3 * sum + self.corners.orientation[i] as i32;
         }
-        self.corner_orientation = sum;
+        sum
     }
 
-    /// Calculates the corner permutation.
+    /// The corner permutation.
     ///
-    /// Should be called after every movement. Calculates a tenary value used
-    /// to represent the corner permutation of the whole cube. Further
-    /// explanation at (http://kociemba.org/math/coordlevel.htm)
-    pub fn calculate_corner_permutation(&mut self) {
+    /// A value between 0 and 40319 used to represent the permutation of the
+    /// corners. Further explanation at
+    /// (http://kociemba.org/math/coordlevel.htm)
+    pub fn corner_permutation(&self) -> i32 {
         let mut sum = 0;
         for i in 1..8 {
-            let mut diff = 0_i32;
+            let mut diff = 0;
             for j in 0..i {
-                if self.corners[j].coordinate as i32 > self.corners[i].coordinate as i32 {
+                if self.corners.permutation[j] > self.corners.permutation[i] {
                     diff = diff + 1;
                 }
             }
-            sum = sum + diff * utility::factorial(i as i64) as i32;
+            sum = sum + diff * FACTORIAL[i];
         }
-        self.corner_permutation = sum;
+        sum
     }
 
-    /// Calculates the edge orientation.
+    /// The edge orientation.
     ///
-    /// Should be called after every movement. Calculates a tenary value used
-    /// to represent the edge orientation of the whole cube.  Further
-    /// explanation at (http://kociemba.org/math/coordlevel.htm)
-    pub fn calculate_edge_orientation(&mut self) {
+    /// A binary value used to represent the edge orientation of the whole
+    /// cube, between 0 and 2047. The last edge is left out as its
+    /// orientation is fixed by the other eleven. Further explanation at
+    /// (http://kociemba.org/math/coordlevel.htm)
+    pub fn edge_orientation(&self) -> i32 {
         let mut sum = 0;
-        for i in 0..12 {
-            sum = sum + self.edges[i].orientation * 2_i32.pow((11 - i) as u32)
+        for i in 0..11 {
+            sum = 2 * sum + self.edges.orientation[i] as i32;
         }
-        self.edge_orientation = sum
+        sum
     }
 
-    /// Calculates the UD Slice.
+    /// The UD Slice.
     ///
     /// This is best explained at the link. Essentially we take the positions
     /// of the UD slices and and any edges in between the positions (and
     /// position "12") are taken. These positions are then used to calculate
     /// a binomial coefficent, with k comibinations of:
     ///     -1 + the number of UD Slices to the left(smaller) than the current.
-    /// Further explanation at (http://kociemba.org/math/UDSliceCoord.htm)
-    pub fn calculate_ud_slice(&mut self) {
-        // FR, FL, BL, BR are the UD slice edges. This corresponds to the last
-        // four values in our edges array, so we take these values and order
-        // them for the algorithm.
+    /// Between 0 and 494. Further explanation at
+    /// (http://kociemba.org/math/UDSliceCoord.htm)
+    pub fn ud_slice(&self) -> i32 {
+        // FR, FL, BL, BR are the UD slice edges, the last four `Edge`s.
         let mut sum = 0;
-        let values = [
-            edge_cubies::Edge::FR,
-            edge_cubies::Edge::FL,
-            edge_cubies::Edge::BL,
-            edge_cubies::Edge::BR,
-        ];
-
         let mut k = -1;
         for i in 0..12 {
-            if values.contains(&self.edges[i].coordinate) == true {
+            if self.edges.permutation[i] >= Edge::FR {
                 k = k + 1;
             } else if k >= 0 {
-                sum = sum + utility::binomial(i as i64, k as i64) as i32;
-            };
+                sum = sum + binomial(i as i32, k);
+            }
         }
-
-        self.ud_slice = sum;
+        sum
     }
 
-    /// Calculates the UD sorted slice.
+    /// The UD sorted slice.
     ///
-    /// The permutation and location of the UD-Slice edges.
-    pub fn calculate_ud_sorted_slice(&mut self) {
-        let mut x: i32 = 0;
+    /// The permutation and location of the UD-Slice edges, between 0 and
+    /// 11879. Between 0 and 23 for a G1 state `Cube`.
+    pub fn ud_sorted_slice(&self) -> i32 {
+        let mut x = 0;
         let mut a = 0;
-        let mut edge4: [edge_cubies::Edge; 4] = [
-            edge_cubies::Edge::UB,
-            edge_cubies::Edge::UB,
-            edge_cubies::Edge::UB,
-            edge_cubies::Edge::UB,
-        ];
+        let mut edge4 = [Edge::UB; 4];
 
         for j in (0..12).rev() {
-            if self.edges[j as usize].coordinate == edge_cubies::Edge::FR
-                || self.edges[j as usize].coordinate == edge_cubies::Edge::FL
-                || self.edges[j as usize].coordinate == edge_cubies::Edge::BL
-                || self.edges[j as usize].coordinate == edge_cubies::Edge::BR
-            {
-                a = a + utility::binomial(11 - j as i64, x as i64 + 1) as i32;
-                edge4[3 - x as usize] = self.edges[j as usize].coordinate;
+            if self.edges.permutation[j] >= Edge::FR {
+                a = a + binomial(11 - j as i32, x + 1);
+                edge4[3 - x as usize] = self.edges.permutation[j];
                 x = x + 1
             }
         }
 
         let mut b = 0;
-
         for j in (0..4).rev() {
-            //println!("{} out of {:?}", j, (0..4).rev());
             let mut k = 0;
-            while edge4[j] as i32 != j as i32 + 8 {
-                //+8
-                //println!("k: {:1}, j: {:2}, edge4: {:?}", k, (j + 8) as i32, edge4[j] as i32);
-                let temp = edge4[0];
-                for i in 0..j {
-                    edge4[i as usize] = edge4[i as usize + 1];
-                    //println!("edge4: {:?}", edge4[i as usize] as i32);
-                }
-                edge4[j as usize] = temp;
-
+            while edge4[j] as usize != j + 8 {
+                edge4[..=j].rotate_left(1);
                 k = k + 1;
             }
-            //println!("Finished {}", j);
-            b = (j + 1) * b + k;
+            b = (j as i32 + 1) * b + k;
         }
 
-        self.ud_sorted_slice = 24 * a + b as i32
+        24 * a + b
     }
-    //uuuuuuuuubffbrfdbdlbrlfllfbflrdddfflflbblddrdrrlrbrbdr
-    /// Calculates the phase two edge permutation.
-    ///
-    /// Calculates a description of the edge coordinates, but is only valid
-    /// in phase two of the algorithm.
-    pub fn calculate_phase_two_edge_permutation(&mut self) {
-        let mut x = 0_i32;
-        let mut edges: Vec<i32> = Vec::new();
 
-        for i in 0..12 {
-            if self.edges[i].coordinate != edge_cubies::Edge::FL
-                && self.edges[i].coordinate != edge_cubies::Edge::FR
-                && self.edges[i].coordinate != edge_cubies::Edge::BL
-                && self.edges[i].coordinate != edge_cubies::Edge::BR
-            {
-                edges.push(self.edges[i].coordinate as i32);
+    /// The phase two edge permutation.
+    ///
+    /// A description of the edge coordinates, but is only valid in phase two
+    /// of the algorithm. The UD slice edges are skipped and the remaining
+    /// eight are ranked the same way as the corner permutation.
+    pub fn phase_two_edge_permutation(&self) -> i32 {
+        let mut edges = [Edge::UR; 8];
+        let mut n = 0;
+        for e in self.edges.permutation.iter() {
+            if *e < Edge::FR && n < 8 {
+                edges[n] = *e;
+                n = n + 1;
             }
         }
 
-        for i in (1..8_i32).rev() {
-            let mut s = 0_i32;
-            for j in (0..(i)).rev() {
-                if (edges[j as usize]) > (edges[i as usize]) {
-                    s = s + 1;
+        let mut sum = 0;
+        for i in 1..8 {
+            let mut diff = 0;
+            for j in 0..i {
+                if edges[j] > edges[i] {
+                    diff = diff + 1;
                 }
             }
-            x = (x + s) * (edges[i as usize] + 1);
+            sum = sum + diff * FACTORIAL[i];
         }
-        self.phase_two_edge_permutation = x;
+        sum
     }
 
-    /// Calculates the parity of the corner permutation.
+    /// The parity of the corner permutation.
     /// Used only for testing if the cube can be solved.
-    pub fn calculate_corner_parity(&mut self) {
+    pub fn corner_parity(&self) -> i32 {
         let mut s = 0;
         for i in (0..8).rev() {
             for j in (0..i).rev() {
-                if (self.corners[j].coordinate) > self.corners[i].coordinate {
+                if self.corners.permutation[j] > self.corners.permutation[i] {
                     s = s + 1;
                 }
             }
         }
-
-        self.corner_parity = s % 2;
+        s % 2
     }
 
-    /// Calculates the parity of the edge permutation.
+    /// The parity of the edge permutation.
     /// Used only for testing if the cube can be solved.
-    pub fn calculate_edge_parity(&mut self) {
+    pub fn edge_parity(&self) -> i32 {
         let mut s = 0;
         for i in (0..12).rev() {
             for j in (0..i).rev() {
-                if self.edges[j].coordinate as i32 > self.edges[i].coordinate as i32 {
+                if self.edges.permutation[j] > self.edges.permutation[i] {
                     s = s + 1;
                 }
             }
         }
-        self.edge_parity = s % 2
+        s % 2
     }
 
-    /// Functions to be called after each move.c
+    /// The cube that undoes this one: doing this cube's moves and then the
+    /// inverse's leaves a solved cube.
     ///
-    /// Used to update the internal state of the variables in the struct
-    /// after movements.
-    pub fn coordinate_adjustments(&mut self) {
-        self.calculate_corner_orientation();
-        self.calculate_corner_permutation();
-        self.calculate_edge_orientation();
-        self.calculate_corner_parity();
-        self.calculate_edge_parity();
-        self.calculate_ud_slice();
-        self.calculate_ud_sorted_slice();
-        //println!("Ud sorted done.");
-        self.calculate_phase_two_edge_permutation();
-        //println!("Phase two edge perm done.");
+    /// # Return
+    /// * `Cube` - The inverse.
+    pub fn inverse(&self) -> Cube {
+        Cube {
+            corners: corner_cubies::inverse(&self.corners),
+            edges: edge_cubies::inverse(&self.edges),
+        }
     }
 
-    /// A clockwise front move.
-    pub fn f(&mut self) {
-        for i in 0..8 {
-            self.corners[i].f();
+    /// Multiplies two cubes, as in doing this cube's moves and then those of
+    /// `other`. This is the operation of the Rubik's cube group, so it is
+    /// associative but `a.multiply(&b)` is not in general `b.multiply(&a)`.
+    ///
+    /// # Parameters
+    /// * `other` - The `Cube` to multiply by.
+    /// # Return
+    /// * `Cube` - The product.
+    pub fn multiply(&self, other: &Cube) -> Cube {
+        Cube {
+            corners: corner_cubies::multiply(&self.corners, &other.corners),
+            edges: edge_cubies::multiply(&self.edges, &other.edges),
+        }
+    }
+
+    /// Multiplies the cube by itself `n` times. A negative `n` repeats the
+    /// inverse instead, and no times at all is a solved cube.
+    ///
+    /// # Parameters
+    /// * `n` - How many times to repeat the cube.
+    /// # Return
+    /// * `Cube` - The power.
+    pub fn pow(&self, n: i32) -> Cube {
+        let mut base = if n < 0 { self.inverse() } else { *self };
+        let mut n = n.unsigned_abs();
+        let mut power = Cube::new();
+        while n > 0 {
+            if n % 2 == 1 {
+                power = power.multiply(&base);
+            }
+            base = base.multiply(&base);
+            n = n / 2;
+        }
+        power
+    }
+
+    /// How many times the cube has to be repeated to get back to solved.
+    ///
+    /// Each cycle of cubies comes home after going round as many times as
+    /// it is long, or three times that (two for edges) if it comes back
+    /// twisted. The order is the least common multiple of those, and is at
+    /// most 1260 for a cube that can be solved.
+    ///
+    /// # Return
+    /// * `u64` - The order of the cube in the Rubik's cube group.
+    pub fn order(&self) -> u64 {
+        let mut order = 1;
+        let mut seen = [false; 8];
+        for start in 0..8 {
+            let (mut length, mut twist, mut i) = (0, 0, start);
+            while !seen[i] {
+                seen[i] = true;
+                twist = twist + self.corners.orientation[i];
+                i = self.corners.permutation[i] as usize;
+                length = length + 1;
+            }
+            if twist % 3 != 0 {
+                length = length * 3;
+            }
+            if length > 0 {
+                order = utility::lcm(order, length);
+            }
         }
+        let mut seen = [false; 12];
+        for start in 0..12 {
+            let (mut length, mut flip, mut i) = (0, 0, start);
+            while !seen[i] {
+                seen[i] = true;
+                flip = flip + self.edges.orientation[i];
+                i = self.edges.permutation[i] as usize;
+                length = length + 1;
+            }
+            if flip % 2 != 0 {
+                length = length * 2;
+            }
+            if length > 0 {
+                order = utility::lcm(order, length);
+            }
+        }
+        order
+    }
+
+    /// Checks that this cube could be reached by turning a solved cube.
+    ///
+    /// # Return
+    /// * `Result<(), Unsolvable>` - Why the cube cannot be solved, if it
+    ///     cannot.
+    pub fn check_solvable(&self) -> Result<(), Unsolvable> {
+        let mut corners_seen = [false; 8];
+        for c in self.corners.permutation.iter() {
+            corners_seen[*c as usize] = true;
+        }
+        let mut edges_seen = [false; 12];
+        for e in self.edges.permutation.iter() {
+            edges_seen[*e as usize] = true;
+        }
+        if corners_seen.contains(&false) || edges_seen.contains(&false) {
+            return Err(Unsolvable::MissingCubies);
+        }
+
+        let mut twist = 0;
+        for o in self.corners.orientation.iter() {
+            if *o > 2 {
+                return Err(Unsolvable::CornerTwist);
+            }
+            twist = twist + *o as i32;
+        }
+        if twist % 3 != 0 {
+            return Err(Unsolvable::CornerTwist);
+        }
+        let mut flip = 0;
+        for o in self.edges.orientation.iter() {
+            if *o > 1 {
+                return Err(Unsolvable::EdgeFlip);
+            }
+            flip = flip + *o as i32;
+        }
+        if flip % 2 != 0 {
+            return Err(Unsolvable::EdgeFlip);
+        }
+
+        if self.corner_parity() != self.edge_parity() {
+            return Err(Unsolvable::Parity);
+        }
+        Ok(())
+    }
+
+    /// Picks a cube uniformly at random from every cube that can be solved.
+    ///
+    /// Both permutations are shuffled, and if their parities differ two edges
+    /// are swapped. That pairs every unsolvable permutation with exactly one
+    /// solvable one, so each solvable cube is still as likely as any other.
+    ///
+    /// # Parameters
+    /// * `rng` - Where the randomness comes from. Seed it for the same cube
+    ///     every time.
+    /// # Return
+    /// * `Cube`
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Cube {
+        let mut c = Cube::new();
+        c.corners.permutation.shuffle(rng);
+        c.edges.permutation.shuffle(rng);
+        if c.corner_parity() != c.edge_parity() {
+            c.edges.permutation.swap(10, 11);
+        }
+        c.set_corner_orientation(rng.gen_range(0..2187));
+        c.set_edge_orientation(rng.gen_range(0..2048));
+        c
+    }
+
+    /// Sets the corner orientation from a coordinate, the inverse of
+    /// `corner_orientation`. Only the orientations are changed.
+    ///
+    /// # Parameters
+    /// * `twist` - A value between 0 and 2186.
+    pub fn set_corner_orientation(&mut self, twist: i32) {
+        let mut twist = twist;
+        let mut sum = 0;
+        for i in (0..7).rev() {
+            self.corners.orientation[i] = (twist % 3) as u8;
+            sum = sum + twist % 3;
+            twist = twist / 3;
+        }
+        self.corners.orientation[7] = ((3 - sum % 3) % 3) as u8;
+    }
+
+    /// Sets the edge orientation from a coordinate, the inverse of
+    /// `edge_orientation`. Only the orientations are changed.
+    ///
+    /// # Parameters
+    /// * `flip` - A value between 0 and 2047.
+    pub fn set_edge_orientation(&mut self, flip: i32) {
+        let mut flip = flip;
+        let mut sum = 0;
+        for i in (0..11).rev() {
+            self.edges.orientation[i] = (flip % 2) as u8;
+            sum = sum + flip % 2;
+            flip = flip / 2;
+        }
+        self.edges.orientation[11] = (sum % 2) as u8;
+    }
+
+    /// Sets the corner permutation from a coordinate, the inverse of
+    /// `corner_permutation`. Only the permutation is changed.
+    ///
+    /// # Parameters
+    /// * `permutation` - A value between 0 and 40319.
+    pub fn set_corner_permutation(&mut self, permutation: i32) {
+        let mut remaining: Vec<usize> = (0..8).collect();
+        for i in (0..8).rev() {
+            // How many corners to the left are bigger than this one.
+            let diff = (permutation / FACTORIAL[i]) % (i as i32 + 1);
+            let c = remaining.remove(remaining.len() - 1 - diff as usize);
+            self.corners.permutation[i] = corner_cubies::CORNERS[c];
+        }
+    }
+
+    /// Sets the UD slice from a coordinate, the inverse of
+    /// `ud_slice`. The UD slice edges are put in the positions
+    /// given, in order, with the other edges in order around them.
+    /// Orientations are left alone.
+    ///
+    /// # Parameters
+    /// * `slice` - A value between 0 and 494.
+    pub fn set_ud_slice(&mut self, slice: i32) {
+        let mut slice = slice;
+        let mut slice_left = 4;
+        let mut is_slice = [false; 12];
+        for i in (0..12).rev() {
+            if slice_left == 0 {
+                break;
+            }
+            // An edge that is not in the slice adds this much to the
+            // coordinate, so if the coordinate is at least this big it
+            // cannot be a slice edge.
+            let c = binomial(i as i32, slice_left - 1);
+            if slice >= c && i as i32 >= slice_left {
+                slice = slice - c;
+            } else {
+                is_slice[i] = true;
+                slice_left = slice_left - 1;
+            }
+        }
+
+        let (mut next_slice, mut next_other) = (8, 0);
         for i in 0..12 {
-            self.edges[i].f();
+            if is_slice[i] {
+                self.edges.permutation[i] = edge_cubies::EDGES[next_slice];
+                next_slice = next_slice + 1;
+            } else {
+                self.edges.permutation[i] = edge_cubies::EDGES[next_other];
+                next_other = next_other + 1;
+            }
         }
-        self.coordinate_adjustments();
     }
 
-    /// A clockwise back move.
-    pub fn b(&mut self) {
-        for i in 0..8 {
-            self.corners[i].b();
+    /// Sets the phase two edge permutation from a coordinate, the inverse of
+    /// `phase_two_edge_permutation`. The UD slice edges are put
+    /// back in their home positions and orientations are left alone.
+    ///
+    /// # Parameters
+    /// * `permutation` - A value between 0 and 40319.
+    pub fn set_phase_two_edge_permutation(&mut self, permutation: i32) {
+        let mut remaining: Vec<usize> = (0..8).collect();
+        for i in (0..8).rev() {
+            let diff = (permutation / FACTORIAL[i]) % (i as i32 + 1);
+            let e = remaining.remove(remaining.len() - 1 - diff as usize);
+            self.edges.permutation[i] = edge_cubies::EDGES[e];
+        }
+        for i in 8..12 {
+            self.edges.permutation[i] = edge_cubies::EDGES[i];
+        }
+    }
+
+    /// Sets the UD sorted slice from a coordinate, the inverse of
+    /// `ud_sorted_slice`. The UD slice edges are put in the
+    /// positions and order given, and the other edges keep their order
+    /// around them. Orientations are left alone.
+    ///
+    /// # Parameters
+    /// * `sorted_slice` - A value between 0 and 11879.
+    pub fn set_ud_sorted_slice(&mut self, sorted_slice: i32) {
+        let mut a = sorted_slice / 24;
+        let b = sorted_slice as usize % 24;
+
+        // The positions, counted back from the last edge, are a
+        // combinatorial number with the furthest back edge biggest.
+        let mut is_slice = [false; 12];
+        let mut p = 11;
+        for x in (0..4).rev() {
+            while binomial(p, x + 1) > a {
+                p = p - 1;
+            }
+            a = a - binomial(p, x + 1);
+            is_slice[11 - p as usize] = true;
+            p = p - 1;
         }
+
+        // Undo the rotations `ud_sorted_slice` counts, last first.
+        let mut edge4 = [8, 9, 10, 11];
+        let turns = [0, b % 2, (b / 2) % 3, b / 6];
+        for j in 1..4 {
+            edge4[..=j].rotate_right(turns[j]);
+        }
+
+        let others: Vec<Edge> = self.edges.permutation.iter().cloned().filter(|e| *e < Edge::FR).collect();
+        let (mut next_slice, mut next_other) = (0, 0);
         for i in 0..12 {
-            self.edges[i].b();
+            if is_slice[i] {
+                self.edges.permutation[i] = edge_cubies::EDGES[edge4[next_slice]];
+                next_slice = next_slice + 1;
+            } else {
+                self.edges.permutation[i] = others[next_other];
+                next_other = next_other + 1;
+            }
         }
-        self.coordinate_adjustments();
     }
 
-    /// A clockwise left move.
-    pub fn l(&mut self) {
+    /// Builds a cube from its phase one coordinates, with the corners and
+    /// the other edges in order.
+    ///
+    /// # Parameters
+    /// * `twist` - The corner orientation, between 0 and 2186.
+    /// * `flip` - The edge orientation, between 0 and 2047.
+    /// * `slice` - The UD slice, between 0 and 494.
+    /// # Return
+    /// * `Cube`
+    pub fn from_phase_one(twist: i32, flip: i32, slice: i32) -> Cube {
+        let mut c = Cube::new();
+        c.set_ud_slice(slice);
+        c.set_corner_orientation(twist);
+        c.set_edge_orientation(flip);
+        c
+    }
+
+    /// Builds a G1 cube from its phase two coordinates.
+    ///
+    /// # Parameters
+    /// * `corner_permutation` - Between 0 and 40319.
+    /// * `edge_permutation` - The phase two edge permutation of the U and D
+    ///     layer edges, between 0 and 40319.
+    /// * `slice_permutation` - The order of the UD slice edges, between 0
+    ///     and 23.
+    /// # Return
+    /// * `Cube`
+    pub fn from_phase_two(corner_permutation: i32, edge_permutation: i32, slice_permutation: i32) -> Cube {
+        let mut c = Cube::new();
+        c.set_corner_permutation(corner_permutation);
+        c.set_phase_two_edge_permutation(edge_permutation);
+        c.set_ud_sorted_slice(slice_permutation);
+        c
+    }
+
+    /// The corners as an array of `CornerCubie`s.
+    ///
+    /// # Return
+    /// * `[CornerCubie; 8]` - The cubie in each corner position.
+    #[allow(deprecated)]
+    pub fn corner_cubies(&self) -> [corner_cubies::CornerCubie; 8] {
+        let mut cubies = [corner_cubies::CornerCubie::new(corner_cubies::Corner::URF); 8];
         for i in 0..8 {
-            self.corners[i].l();
+            cubies[i] = corner_cubies::CornerCubie::new(self.corners.permutation[i]);
+            cubies[i].orientation = self.corners.orientation[i] as i32;
         }
+        cubies
+    }
+
+    /// The edges as an array of `EdgeCubie`s.
+    ///
+    /// # Return
+    /// * `[EdgeCubie; 12]` - The cubie in each edge position.
+    #[allow(deprecated)]
+    pub fn edge_cubies(&self) -> [edge_cubies::EdgeCubie; 12] {
+        let mut cubies = [edge_cubies::EdgeCubie::new(Edge::UR); 12];
         for i in 0..12 {
-            self.edges[i].l();
+            cubies[i] = edge_cubies::EdgeCubie::new(self.edges.permutation[i]);
+            cubies[i].orientation = self.edges.orientation[i] as i32;
         }
-        self.coordinate_adjustments();
+        cubies
     }
 
-    /// A clockwise right move.
-    pub fn r(&mut self) {
+    /// Builds a cube from arrays of `CornerCubie`s and `EdgeCubie`s, the
+    /// inverse of `corner_cubies` and `edge_cubies`.
+    ///
+    /// # Parameters
+    /// * `corners` - The cubie in each corner position.
+    /// * `edges` - The cubie in each edge position.
+    /// # Return
+    /// * `Cube`
+    #[allow(deprecated)]
+    pub fn from_cubies(corners: &[corner_cubies::CornerCubie; 8], edges: &[edge_cubies::EdgeCubie; 12]) -> Cube {
+        let mut c = Cube::new();
         for i in 0..8 {
-            self.corners[i].r();
+            c.corners.permutation[i] = corners[i].coordinate;
+            c.corners.orientation[i] = corners[i].orientation as u8;
         }
         for i in 0..12 {
-            self.edges[i].r();
+            c.edges.permutation[i] = edges[i].coordinate;
+            c.edges.orientation[i] = edges[i].orientation as u8;
         }
-        self.coordinate_adjustments();
+        c
     }
 
-    /// A clockwise upper move.
-    pub fn u(&mut self) {
+    /// Sets every orientation from how far each cubie is from its home
+    /// position.
+    #[deprecated(note = "orientations are kept up to date by the moves")]
+    pub fn calculate_orientations_init(&mut self) {
         for i in 0..8 {
-            self.corners[i].u();
+            let c = self.corners.permutation[i] as i32;
+            self.corners.orientation[i] = ((((i as i32 - c) % 3) + 3) % 3) as u8;
         }
         for i in 0..12 {
-            self.edges[i].u();
+            let e = self.edges.permutation[i] as i32;
+            self.edges.orientation[i] = ((((i as i32 - e) % 4) + 4) % 4) as u8;
         }
-        self.coordinate_adjustments();
+    }
+
+    /// Does nothing. The corner orientation is worked out by
+    /// `corner_orientation` when asked for.
+    #[deprecated(note = "use `corner_orientation()`")]
+    pub fn calculate_corner_orientation(&mut self) {}
+
+    /// Does nothing. The corner permutation is worked out by
+    /// `corner_permutation` when asked for.
+    #[deprecated(note = "use `corner_permutation()`")]
+    pub fn calculate_corner_permutation(&mut self) {}
+
+    /// Does nothing. The edge orientation is worked out by
+    /// `edge_orientation` when asked for.
+    #[deprecated(note = "use `edge_orientation()`")]
+    pub fn calculate_edge_orientation(&mut self) {}
+
+    /// Does nothing. The UD slice is worked out by `ud_slice` when asked
+    /// for.
+    #[deprecated(note = "use `ud_slice()`")]
+    pub fn calculate_ud_slice(&mut self) {}
+
+    /// Does nothing. The UD sorted slice is worked out by `ud_sorted_slice`
+    /// when asked for.
+    #[deprecated(note = "use `ud_sorted_slice()`")]
+    pub fn calculate_ud_sorted_slice(&mut self) {}
+
+    /// Does nothing. The phase two edge permutation is worked out by
+    /// `phase_two_edge_permutation` when asked for.
+    #[deprecated(note = "use `phase_two_edge_permutation()`")]
+    pub fn calculate_phase_two_edge_permutation(&mut self) {}
+
+    /// Does nothing. The corner parity is worked out by `corner_parity`
+    /// when asked for.
+    #[deprecated(note = "use `corner_parity()`")]
+    pub fn calculate_corner_parity(&mut self) {}
+
+    /// Does nothing. The edge parity is worked out by `edge_parity` when
+    /// asked for.
+    #[deprecated(note = "use `edge_parity()`")]
+    pub fn calculate_edge_parity(&mut self) {}
+
+    /// Does nothing. Every coordinate is worked out from the cubies when
+    /// asked for, so there is nothing to bring up to date.
+    #[deprecated(note = "coordinates are worked out when asked for")]
+    pub fn coordinate_adjustments(&mut self) {}
+
+    /// A clockwise front move.
+    pub fn f(&mut self) {
+        *self = self.multiply(&BASIC_MOVES[0]);
+    }
+
+    /// A clockwise back move.
+    pub fn b(&mut self) {
+        *self = self.multiply(&BASIC_MOVES[1]);
+    }
+
+    /// A clockwise left move.
+    pub fn l(&mut self) {
+        *self = self.multiply(&BASIC_MOVES[4]);
+    }
+
+    /// A clockwise right move.
+    pub fn r(&mut self) {
+        *self = self.multiply(&BASIC_MOVES[5]);
+    }
+
+    /// A clockwise upper move.
+    pub fn u(&mut self) {
+        *self = self.multiply(&BASIC_MOVES[2]);
     }
 
     /// A clockwise down move.
     pub fn d(&mut self) {
-        for i in 0..8 {
-            self.corners[i].d();
-        }
-        for i in 0..12 {
-            self.edges[i].d();
+        *self = self.multiply(&BASIC_MOVES[3]);
+    }
+
+    /// Turns the whole cube like a right move.
+    ///
+    /// The centres stay where they are in this model, so instead the faces
+    /// are renamed: the front becomes the up face, and so on.
+    pub fn x(&mut self) {
+        *self = symmetry::conjugate(self, symmetry::rotations()[0]);
+    }
+
+    /// Turns the whole cube like an upper move.
+    pub fn y(&mut self) {
+        *self = symmetry::conjugate(self, symmetry::rotations()[1]);
+    }
+
+    /// Turns the whole cube like a front move.
+    pub fn z(&mut self) {
+        *self = symmetry::conjugate(self, symmetry::rotations()[2]);
+    }
+
+    /// A middle slice move, between the left and right faces and turning
+    /// like a left move. Done as `R L' x'`, so the centres stay put.
+    pub fn m(&mut self) {
+        self.r();
+        self.turn(Cube::l, 3);
+        self.turn(Cube::x, 3);
+    }
+
+    /// An equator slice move, between the upper and down faces and turning
+    /// like a down move. Done as `U D' y'`.
+    pub fn e(&mut self) {
+        self.u();
+        self.turn(Cube::d, 3);
+        self.turn(Cube::y, 3);
+    }
+
+    /// A standing slice move, between the front and back faces and turning
+    /// like a front move. Done as `F' B z`.
+    pub fn s(&mut self) {
+        self.turn(Cube::f, 3);
+        self.b();
+        self.z();
+    }
+
+    /// Does `movement` `times` times.
+    fn turn(&mut self, movement: fn(&mut Cube), times: usize) {
+        for _ in 0..times {
+            movement(self);
         }
-        self.coordinate_adjustments();
     }
 }
 
@@ -381,6 +861,9 @@ impl Cube {
 #[allow(dead_code)]
 mod tests {
     use super::*;
+    use facelets::moves;
+    use facelets::{Face, IFace};
+    use solver::Moves;
 
     fn rubiks_dummy(
         corners: [corner_cubies::Corner; 8],
@@ -390,12 +873,12 @@ mod tests {
     ) -> Cube {
         let mut test_rubiks = Cube::new();
         for i in 0..12 {
-            test_rubiks.edges[i].coordinate = edges[i];
-            test_rubiks.edges[i].orientation = edge_orientation_values[i]
+            test_rubiks.edges.permutation[i] = edges[i];
+            test_rubiks.edges.orientation[i] = edge_orientation_values[i] as u8
         }
         for i in 0..8 {
-            test_rubiks.corners[i].coordinate = corners[i];
-            test_rubiks.corners[i].orientation = corners_orientation_values[i]
+            test_rubiks.corners.permutation[i] = corners[i];
+            test_rubiks.corners.orientation[i] = corners_orientation_values[i] as u8
         }
 
         test_rubiks
@@ -434,51 +917,270 @@ mod tests {
 
     #[test]
     fn test_calculate_corner_orientation() {
-        let mut test = test_cube_1();
-        test.calculate_corner_orientation();
-        assert_eq!(test.corner_orientation, 1188);
+        let test = test_cube_1();
+        assert_eq!(test.corner_orientation(), 1188);
     }
     #[test]
     fn test_calculate_corner_permutation() {
-        let mut test = test_cube_1();
-        test.calculate_corner_permutation();
-        assert_eq!(test.corner_permutation, 22235);
+        let test = test_cube_1();
+        assert_eq!(test.corner_permutation(), 22235);
     }
     #[test]
     fn test_calculate_edge_orientation() {
-        let mut test = test_cube_1();
-        test.calculate_edge_orientation();
-        assert_eq!(test.edge_orientation, 3098);
+        let test = test_cube_1();
+        assert_eq!(test.edge_orientation(), 1548);
     }
     #[test]
     fn test_calculate_ud_slice() {
-        let mut test = test_cube_1();
-        test.calculate_ud_slice();
-        assert_eq!(test.ud_slice, 307);
+        let test = test_cube_1();
+        assert_eq!(test.ud_slice(), 307);
     }
     #[test]
     // The reason we have a ud_sorted_slice outside of its region is because
     // it is not a G1 state cube.
     fn test_calculate_ud_sorted_slice() {
-        let mut test = test_cube_1();
-        test.calculate_ud_slice();
-        test.calculate_ud_sorted_slice();
-        assert_eq!(test.ud_sorted_slice, 7385);
+        let test = test_cube_1();
+        assert_eq!(test.ud_sorted_slice(), 4495);
     }
 
     #[test]
     fn test_calculate_phase_two_edge_permutation() {
-        let mut test = test_cube_1();
-        test.calculate_phase_two_edge_permutation();
-        assert_eq!(test.phase_two_edge_permutation, 131364);
+        let test = test_cube_1();
+        assert_eq!(test.phase_two_edge_permutation(), 16086);
     }
 
-    fn test_coordinate_adjustments() {}
+    #[test]
+    #[allow(deprecated)]
+    fn test_old_cubies() {
+        let mut c = test_cube_1();
+        let (corners, edges) = (c.corner_cubies(), c.edge_cubies());
+        assert_eq!(corners[0].coordinate, corner_cubies::Corner::DRB);
+        assert_eq!(corners[2].orientation, 2);
+        assert_eq!(edges[2].coordinate, edge_cubies::Edge::BR);
+        assert_eq!(Cube::from_cubies(&corners, &edges), c);
+
+        let before = c;
+        c.coordinate_adjustments();
+        c.calculate_corner_orientation();
+        assert_eq!(c, before);
+    }
+
+    #[test]
+    fn test_check_solvable() {
+        let mut c = Cube::new();
+        c.r();
+        c.u();
+        assert_eq!(c.check_solvable(), Ok(()));
+
+        let mut flipped = c;
+        flipped.edges.orientation[3] = 2;
+        assert_eq!(flipped.check_solvable(), Err(Unsolvable::EdgeFlip));
+        let mut twisted = c;
+        twisted.corners.orientation[0] = 3;
+        assert_eq!(twisted.check_solvable(), Err(Unsolvable::CornerTwist));
+        let mut missing = c;
+        missing.edges.permutation[0] = missing.edges.permutation[1];
+        assert_eq!(missing.check_solvable(), Err(Unsolvable::MissingCubies));
+    }
+
+    #[test]
+    fn test_rotations() {
+        let turned = |moves: &[fn(&mut Cube)]| {
+            let mut c = Cube::new();
+            for movement in moves.iter() {
+                movement(&mut c);
+            }
+            c
+        };
+        // After a rotation the moves name different faces.
+        assert_eq!(turned(&[Cube::x, Cube::u, Cube::x, Cube::x, Cube::x]), turned(&[Cube::f]));
+        assert_eq!(turned(&[Cube::y, Cube::f, Cube::y, Cube::y, Cube::y]), turned(&[Cube::r]));
+        assert_eq!(turned(&[Cube::z, Cube::u, Cube::z, Cube::z, Cube::z]), turned(&[Cube::l]));
+        assert_eq!(turned(&[Cube::r, Cube::x, Cube::x, Cube::x, Cube::x]), turned(&[Cube::r]));
+    }
+
+    #[test]
+    fn test_slices() {
+        // M2 U M2 U2 M2 U M2 swaps opposite edges of the upper face and
+        // leaves everything else, centres included, where it was.
+        let mut c = Cube::new();
+        let h_perm: [fn(&mut Cube); 12] = [
+            Cube::m, Cube::m, Cube::u, Cube::m, Cube::m, Cube::u,
+            Cube::u, Cube::m, Cube::m, Cube::u, Cube::m, Cube::m,
+        ];
+        for movement in h_perm.iter() {
+            movement(&mut c);
+        }
+        let mut expected = Cube::new();
+        expected.edges.permutation.swap(0, 2);
+        expected.edges.permutation.swap(1, 3);
+        assert_eq!(c, expected);
+
+        for slice in [Cube::m, Cube::e, Cube::s].iter() {
+            let mut c = Cube::new();
+            for _ in 0..4 {
+                slice(&mut c);
+            }
+            assert_eq!(c, Cube::new());
+        }
+    }
+
+    #[test]
+    fn test_group() {
+        let scramble = |moves: &[usize]| {
+            let mut c = Cube::new();
+            for m in moves.iter() {
+                c = c.multiply(&basic_moves()[*m]);
+            }
+            c
+        };
+        let a = scramble(&[0, 5, 2, 2, 4]);
+        let b = scramble(&[3, 1, 5]);
+        let c = scramble(&[2, 4, 4, 0]);
+        let solved = Cube::new();
+
+        assert_eq!(a.multiply(&b).multiply(&c), a.multiply(&b.multiply(&c)));
+        assert_eq!(a.multiply(&a.inverse()), solved);
+        assert_eq!(a.inverse().multiply(&a), solved);
+        assert_eq!(a.multiply(&solved), a);
+        assert_ne!(a.multiply(&b), b.multiply(&a));
+        assert_eq!(a.pow(-1), a.inverse());
+        assert_eq!(a.pow(0), solved);
+        assert_eq!(a.pow(3), a.multiply(&a).multiply(&a));
+
+        let mut turned = Cube::new();
+        turned.r();
+        turned.u();
+        assert_eq!(turned, scramble(&[5, 2]));
+        assert_eq!(turned.order(), 105);
+        assert_eq!(turned.pow(105), solved);
+        assert_ne!(turned.pow(35), solved);
+        assert_eq!(basic_moves()[0].order(), 4);
+        assert_eq!(solved.order(), 1);
+        assert_eq!(a.pow(a.order() as i32), solved);
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let powers: HashSet<Cube> = (0..8).map(|n| basic_moves()[0].pow(n)).collect();
+        assert_eq!(powers.len(), 4);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let mut rubiks = Cube::new();
+        rubiks.f();
+        rubiks.r();
+        rubiks.u();
+        rubiks.u();
+        let mut undo = rubiks.inverse();
+        assert_eq!(undo.inverse().corner_orientation(), rubiks.corner_orientation());
+        assert_eq!(undo.inverse().edge_orientation(), rubiks.edge_orientation());
+
+        // The inverse of F R U2 is U2 R' F'.
+        undo = Cube::new();
+        undo.u();
+        undo.u();
+        for _ in 0..3 {
+            undo.r();
+        }
+        for _ in 0..3 {
+            undo.f();
+        }
+        let inverse = rubiks.inverse();
+        for i in 0..8 {
+            assert_eq!(inverse.corners.permutation[i], undo.corners.permutation[i]);
+            assert_eq!(inverse.corners.orientation[i], undo.corners.orientation[i]);
+        }
+        for i in 0..12 {
+            assert_eq!(inverse.edges.permutation[i], undo.edges.permutation[i]);
+            assert_eq!(inverse.edges.orientation[i], undo.edges.orientation[i]);
+        }
+    }
+
+    #[test]
+    fn test_setters_round_trip() {
+        let mut rubiks = Cube::new();
+        for twist in 0..2187 {
+            rubiks.set_corner_orientation(twist);
+            assert_eq!(rubiks.corner_orientation(), twist);
+        }
+        for flip in 0..2048 {
+            rubiks.set_edge_orientation(flip);
+            assert_eq!(rubiks.edge_orientation(), flip);
+        }
+        for slice in 0..495 {
+            rubiks.set_ud_slice(slice);
+            assert_eq!(rubiks.ud_slice(), slice);
+        }
+        let mut rubiks = Cube::new();
+        for permutation in 0..40320 {
+            rubiks.set_corner_permutation(permutation);
+            assert_eq!(rubiks.corner_permutation(), permutation);
+            rubiks.set_phase_two_edge_permutation(permutation);
+            assert_eq!(rubiks.phase_two_edge_permutation(), permutation);
+        }
+        for sorted_slice in 0..11880 {
+            rubiks.set_ud_sorted_slice(sorted_slice);
+            assert_eq!(rubiks.ud_sorted_slice(), sorted_slice);
+        }
+    }
+
+    #[test]
+    fn test_random() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut parities = [0; 2];
+        for _ in 0..200 {
+            let c = Cube::random(&mut rng);
+            assert_eq!(c.check_solvable(), Ok(()));
+            parities[c.corner_parity() as usize] = parities[c.corner_parity() as usize] + 1;
+        }
+        // Both parities turn up about as often as each other.
+        assert!(parities[0] > 60 && parities[1] > 60);
+
+        let a = Cube::random(&mut StdRng::seed_from_u64(1));
+        let b = Cube::random(&mut StdRng::seed_from_u64(1));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_from_coordinates() {
+        let c = Cube::from_phase_one(1188, 1548, 307);
+        assert_eq!(
+            (c.corner_orientation(), c.edge_orientation(), c.ud_slice()),
+            (1188, 1548, 307)
+        );
+
+        for slice_permutation in 0..24 {
+            let c = Cube::from_phase_two(22235, 16086, slice_permutation);
+            assert_eq!(c.corner_permutation(), 22235);
+            assert_eq!(c.phase_two_edge_permutation(), 16086);
+            assert_eq!(c.ud_sorted_slice(), slice_permutation);
+        }
+
+        // The coordinates of a turned cube build it back.
+        let mut turned = Cube::new();
+        turned.u();
+        turned.r();
+        turned.r();
+        turned.d();
+        let c = Cube::from_phase_two(
+            turned.corner_permutation(),
+            turned.phase_two_edge_permutation(),
+            turned.ud_sorted_slice(),
+        );
+        assert_eq!(c, turned);
+    }
 
     #[test]
     fn test_f() {
         let mut rubiks = Cube::new();
-        let mut test_rubiks = rubiks_dummy(
+        let test_rubiks = rubiks_dummy(
             [
                 corner_cubies::Corner::UFL,
                 corner_cubies::Corner::DLF,
@@ -507,35 +1209,61 @@ mod tests {
             [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 1],
         );
 
-        test_rubiks.corner_orientation = 1236;
-        test_rubiks.edge_orientation = 1100;
-        test_rubiks.ud_slice = 33;
 
         rubiks.f();
         for i in 0..8 {
             assert_eq!(
-                rubiks.corners[i].coordinate,
-                test_rubiks.corners[i].coordinate
+                rubiks.corners.permutation[i],
+                test_rubiks.corners.permutation[i]
             );
-            assert_eq!(rubiks.edges[i].coordinate, test_rubiks.edges[i].coordinate);
+            assert_eq!(rubiks.edges.permutation[i], test_rubiks.edges.permutation[i]);
         }
         for i in 8..12 {
-            assert_eq!(rubiks.edges[i].coordinate, test_rubiks.edges[i].coordinate);
+            assert_eq!(rubiks.edges.permutation[i], test_rubiks.edges.permutation[i]);
         }
 
-        assert_eq!(rubiks.corner_orientation, test_rubiks.corner_orientation);
-        assert_eq!(rubiks.edge_orientation, test_rubiks.edge_orientation);
+        assert_eq!(rubiks.corner_orientation(), 1236);
+        assert_eq!(rubiks.edge_orientation(), 550);
 
-        assert_eq!(rubiks.ud_slice, test_rubiks.ud_slice);
+        assert_eq!(rubiks.ud_slice(), 33);
+        check_against_stickers(Cube::f, Moves::F1);
     }
 
-    fn test_b() {}
+    /// Checks a face turn against the same turn of the stickers in
+    /// `facelets::moves`, for one to four quarter turns.
+    fn check_against_stickers(turn: fn(&mut Cube), movement: Moves) {
+        let mut rubiks = Cube::new();
+        let mut face = Face::new_clean();
+        for _ in 0..4 {
+            turn(&mut rubiks);
+            face = moves::do_move(face, movement);
+            assert_eq!(face.turn_into_cube(), rubiks);
+        }
+        assert_eq!(rubiks, Cube::new());
+    }
+
+    #[test]
+    fn test_b() {
+        check_against_stickers(Cube::b, Moves::B1);
+    }
 
-    fn test_l() {}
+    #[test]
+    fn test_l() {
+        check_against_stickers(Cube::l, Moves::L1);
+    }
 
-    fn test_r() {}
+    #[test]
+    fn test_r() {
+        check_against_stickers(Cube::r, Moves::R1);
+    }
 
-    fn test_u() {}
+    #[test]
+    fn test_u() {
+        check_against_stickers(Cube::u, Moves::U1);
+    }
 
-    fn test_d() {}
+    #[test]
+    fn test_d() {
+        check_against_stickers(Cube::d, Moves::D1);
+    }
 }
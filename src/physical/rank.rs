@@ -0,0 +1,245 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! A single number for every state of the cube, and a short code for it.
+//!
+//! The rank is built from the same coordinates the solver uses: the corner
+//! permutation and twist, the edge flip, and the permutation of all twelve
+//! edges. The edge permutation always has the same parity as the corner
+//! permutation, so only half of the edge permutations are counted, and the
+//! rank runs from 0 to one less than `N_STATES` with nothing wasted.
+
+use std::error::Error;
+use std::fmt;
+
+use physical::{edge_cubies, Cube, Unsolvable};
+use utility;
+
+/// The number of states a cube can be in.
+pub const N_STATES: u128 = 43_252_003_274_489_856_000;
+
+const N_TWIST: u128 = 2187;
+const N_FLIP: u128 = 2048;
+/// Edge permutations with the parity fixed, 12! / 2.
+const N_EDGE_PERMUTATION: u128 = 239_500_800;
+
+/// The digits of a code, leaving out I, L, O and U so they are hard to
+/// misread.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// How many digits a code has. 32^14 is the first power over `N_STATES`.
+pub const CODE_LENGTH: usize = 14;
+
+/// Why a code could not be read.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    /// The code is not `CODE_LENGTH` digits long.
+    Length(usize),
+    /// A character that is not one of the digits.
+    BadCharacter(char),
+    /// The code is past the last state.
+    OutOfRange,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodeError::Length(length) => write!(f, "a code has {} characters, not {}", CODE_LENGTH, length),
+            CodeError::BadCharacter(c) => write!(f, "`{}` is not part of a code", c),
+            CodeError::OutOfRange => write!(f, "the code is too big to be a cube"),
+        }
+    }
+}
+
+impl Error for CodeError {}
+
+/// Ranks a cube among every state it could be in.
+///
+/// # Parameters
+/// * `rubiks` - The `Cube` to rank.
+/// # Returns
+/// * `Result<u128, Unsolvable>` - A number below `N_STATES`, or why the
+///     cube has none.
+pub fn rank(rubiks: &Cube) -> Result<u128, Unsolvable> {
+    rubiks.check_solvable()?;
+
+    let mut rank = rubiks.corner_permutation() as u128;
+    rank = rank * N_TWIST + rubiks.corner_orientation() as u128;
+    rank = rank * N_EDGE_PERMUTATION + edge_permutation(rubiks);
+    rank = rank * N_FLIP + rubiks.edge_orientation() as u128;
+    Ok(rank)
+}
+
+/// Builds the cube with a given rank, the inverse of `rank`.
+///
+/// # Parameters
+/// * `rank` - A number below `N_STATES`.
+/// # Returns
+/// * `Option<Cube>` - The cube, or None if `rank` is too big.
+pub fn unrank(rank: u128) -> Option<Cube> {
+    if rank >= N_STATES {
+        return None;
+    }
+    let mut rank = rank;
+    let flip = rank % N_FLIP;
+    rank = rank / N_FLIP;
+    let edges = rank % N_EDGE_PERMUTATION;
+    rank = rank / N_EDGE_PERMUTATION;
+    let twist = rank % N_TWIST;
+    let corners = rank / N_TWIST;
+
+    let mut c = Cube::new();
+    c.set_corner_permutation(corners as i32);
+    set_edge_permutation(&mut c, edges);
+    c.set_corner_orientation(twist as i32);
+    c.set_edge_orientation(flip as i32);
+    Some(c)
+}
+
+/// Writes the rank of a cube as a code of `CODE_LENGTH` digits.
+///
+/// # Parameters
+/// * `rubiks` - The `Cube` to write.
+/// # Returns
+/// * `Result<String, Unsolvable>` - The code, or why the cube has none.
+pub fn encode(rubiks: &Cube) -> Result<String, Unsolvable> {
+    let mut rank = rank(rubiks)?;
+    let mut code = vec![b'0'; CODE_LENGTH];
+    for digit in code.iter_mut().rev() {
+        *digit = ALPHABET[(rank % 32) as usize];
+        rank = rank / 32;
+    }
+    Ok(String::from_utf8(code).unwrap())
+}
+
+/// Reads a code written by `encode`. Lower case letters are accepted too.
+///
+/// # Parameters
+/// * `code` - The code to read.
+/// # Returns
+/// * `Result<Cube, CodeError>` - The cube, or what is wrong with the code.
+pub fn decode(code: &str) -> Result<Cube, CodeError> {
+    let length = code.chars().count();
+    if length != CODE_LENGTH {
+        return Err(CodeError::Length(length));
+    }
+    let mut rank: u128 = 0;
+    for c in code.chars() {
+        let digit = ALPHABET
+            .iter()
+            .position(|d| *d as char == c.to_ascii_uppercase())
+            .ok_or(CodeError::BadCharacter(c))?;
+        rank = rank * 32 + digit as u128;
+    }
+    unrank(rank).ok_or(CodeError::OutOfRange)
+}
+
+/// Ranks the permutation of all twelve edges, the same way as the corner
+/// permutation but leaving out the first digit, which the parity decides.
+fn edge_permutation(rubiks: &Cube) -> u128 {
+    let mut sum = 0;
+    for i in 2..12 {
+        let mut diff = 0;
+        for j in 0..i {
+            if rubiks.edges.permutation[j] > rubiks.edges.permutation[i] {
+                diff = diff + 1;
+            }
+        }
+        sum = sum + diff * utility::factorial(i as i64) as u128 / 2;
+    }
+    sum
+}
+
+/// Sets the permutation of all twelve edges from `edge_permutation`, giving
+/// it the same parity as the corners already on the cube.
+fn set_edge_permutation(rubiks: &mut Cube, permutation: u128) {
+    let mut digits = [0; 12];
+    let mut parity = rubiks.corner_parity() as usize;
+    for i in 2..12 {
+        digits[i] = ((permutation / (utility::factorial(i as i64) as u128 / 2)) % (i as u128 + 1)) as usize;
+        parity = parity + digits[i];
+    }
+    // Every digit counts edges out of order, so their total is the parity.
+    digits[1] = parity % 2;
+
+    let mut remaining: Vec<usize> = (0..12).collect();
+    for i in (0..12).rev() {
+        let e = remaining.remove(remaining.len() - 1 - digits[i]);
+        rubiks.edges.permutation[i] = edge_cubies::EDGES[e];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_rank_bounds() {
+        assert_eq!(N_STATES, 40320 * N_TWIST * N_EDGE_PERMUTATION * N_FLIP);
+        assert_eq!(rank(&Cube::new()), Ok(0));
+        assert_eq!(unrank(0), Some(Cube::new()));
+        assert!(unrank(N_STATES - 1).is_some());
+        assert_eq!(unrank(N_STATES), None);
+        assert_eq!(rank(&unrank(N_STATES - 1).unwrap()), Ok(N_STATES - 1));
+
+        let mut twisted = Cube::new();
+        twisted.corners.orientation[0] = 1;
+        assert_eq!(rank(&twisted), Err(Unsolvable::CornerTwist));
+    }
+
+    #[test]
+    fn test_rank_round_trip() {
+        // Every value of each part on its own.
+        for corners in 0..40320 {
+            let r = corners * N_TWIST * N_EDGE_PERMUTATION * N_FLIP;
+            assert_eq!(rank(&unrank(r).unwrap()), Ok(r));
+        }
+        for twist in 0..N_TWIST {
+            let r = twist * N_EDGE_PERMUTATION * N_FLIP;
+            assert_eq!(rank(&unrank(r).unwrap()), Ok(r));
+        }
+        for flip in 0..N_FLIP {
+            assert_eq!(rank(&unrank(flip).unwrap()), Ok(flip));
+        }
+        for edges in (0..N_EDGE_PERMUTATION).step_by(9973) {
+            let r = edges * N_FLIP;
+            assert_eq!(rank(&unrank(r).unwrap()), Ok(r));
+        }
+
+        let mut rng = StdRng::seed_from_u64(17);
+        for _ in 0..5000 {
+            let r = rng.gen_range(0..N_STATES);
+            let c = unrank(r).unwrap();
+            assert_eq!(c.check_solvable(), Ok(()));
+            assert_eq!(rank(&c), Ok(r));
+
+            let c = Cube::random(&mut rng);
+            assert_eq!(unrank(rank(&c).unwrap()), Some(c));
+        }
+    }
+
+    #[test]
+    fn test_codes() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let c = Cube::random(&mut rng);
+            let code = encode(&c).unwrap();
+            assert_eq!(code.len(), CODE_LENGTH);
+            assert_eq!(decode(&code), Ok(c));
+            assert_eq!(decode(&code.to_lowercase()), Ok(c));
+        }
+        assert_eq!(encode(&Cube::new()).unwrap(), "00000000000000");
+        assert_eq!(decode("0000"), Err(CodeError::Length(4)));
+        assert_eq!(decode("0000000000000U"), Err(CodeError::BadCharacter('U')));
+        assert_eq!(decode("ZZZZZZZZZZZZZZ"), Err(CodeError::OutOfRange));
+    }
+}
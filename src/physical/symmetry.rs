@@ -0,0 +1,357 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! The 48 symmetries of the cube.
+//!
+//! Every symmetry is a rotation of the whole cube, possibly followed by a
+//! mirror, and is represented as a `Cube` so it can be multiplied with
+//! others. They are all built from four basic symmetries: a 120 degree turn
+//! around the URF-DBL corner axis, a 180 degree turn around the F-B axis, a
+//! 90 degree turn around the U-D axis and a mirror in the plane between L
+//! and R. Symmetry `s` is `URF3^a * F2^b * U4^c * LR2^d` with
+//! `s = 16a + 8b + 2c + d`, so the first 16 keep the UD axis where it is.
+//! Further explanation at (http://kociemba.org/math/symmetric.htm)
+
+use std::sync::OnceLock;
+
+use physical::corner_cubies::{self, Corner, CornerCubies};
+use physical::edge_cubies::{self, Edge, EdgeCubies};
+use physical::Cube;
+
+/// The number of symmetries of the cube.
+pub const N_SYM: usize = 48;
+/// The number of symmetries that keep the UD axis fixed.
+pub const N_SYM_D4H: usize = 16;
+/// The symmetries that turn the UD, FB and RL axes onto the UD axis.
+pub const AXES: [usize; 3] = [0, 16, 32];
+
+/// The symmetry `Cube`s and which of them undoes which.
+struct Symmetries {
+    cubes: Vec<Cube>,
+    inverse: Vec<usize>,
+}
+
+fn symmetry_table() -> &'static Symmetries {
+    static SYMMETRIES: OnceLock<Symmetries> = OnceLock::new();
+    SYMMETRIES.get_or_init(|| {
+        let rot_urf3 = basic_symmetry(
+            [
+                Corner::URF,
+                Corner::DFR,
+                Corner::DLF,
+                Corner::UFL,
+                Corner::UBR,
+                Corner::DRB,
+                Corner::DBL,
+                Corner::ULB,
+            ],
+            [1, 2, 1, 2, 2, 1, 2, 1],
+            [
+                Edge::UF,
+                Edge::FR,
+                Edge::DF,
+                Edge::FL,
+                Edge::UB,
+                Edge::BR,
+                Edge::DB,
+                Edge::BL,
+                Edge::UR,
+                Edge::DR,
+                Edge::DL,
+                Edge::UL,
+            ],
+            [1, 0, 1, 0, 1, 0, 1, 0, 1, 1, 1, 1],
+        );
+        let rot_f2 = basic_symmetry(
+            [
+                Corner::DLF,
+                Corner::DFR,
+                Corner::DRB,
+                Corner::DBL,
+                Corner::UFL,
+                Corner::URF,
+                Corner::UBR,
+                Corner::ULB,
+            ],
+            [0; 8],
+            [
+                Edge::DL,
+                Edge::DF,
+                Edge::DR,
+                Edge::DB,
+                Edge::UL,
+                Edge::UF,
+                Edge::UR,
+                Edge::UB,
+                Edge::FL,
+                Edge::FR,
+                Edge::BR,
+                Edge::BL,
+            ],
+            [0; 12],
+        );
+        let rot_u4 = basic_symmetry(
+            [
+                Corner::UBR,
+                Corner::URF,
+                Corner::UFL,
+                Corner::ULB,
+                Corner::DRB,
+                Corner::DFR,
+                Corner::DLF,
+                Corner::DBL,
+            ],
+            [0; 8],
+            [
+                Edge::UB,
+                Edge::UR,
+                Edge::UF,
+                Edge::UL,
+                Edge::DB,
+                Edge::DR,
+                Edge::DF,
+                Edge::DL,
+                Edge::BR,
+                Edge::FR,
+                Edge::FL,
+                Edge::BL,
+            ],
+            [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1],
+        );
+        // Corner orientations of 3 mark a mirrored cube.
+        let mirr_lr2 = basic_symmetry(
+            [
+                Corner::UFL,
+                Corner::URF,
+                Corner::UBR,
+                Corner::ULB,
+                Corner::DLF,
+                Corner::DFR,
+                Corner::DRB,
+                Corner::DBL,
+            ],
+            [3; 8],
+            [
+                Edge::UL,
+                Edge::UF,
+                Edge::UR,
+                Edge::UB,
+                Edge::DL,
+                Edge::DF,
+                Edge::DR,
+                Edge::DB,
+                Edge::FL,
+                Edge::FR,
+                Edge::BR,
+                Edge::BL,
+            ],
+            [0; 12],
+        );
+
+        let mut cubes = Vec::with_capacity(N_SYM);
+        let mut c = Cube::new();
+        for _urf3 in 0..3 {
+            for _f2 in 0..2 {
+                for _u4 in 0..4 {
+                    for _lr2 in 0..2 {
+                        cubes.push(c);
+                        c = c.multiply(&mirr_lr2);
+                    }
+                    c = c.multiply(&rot_u4);
+                }
+                c = c.multiply(&rot_f2);
+            }
+            c = c.multiply(&rot_urf3);
+        }
+
+        let solved = Cube::new();
+        let mut inverse = vec![0; N_SYM];
+        for i in 0..N_SYM {
+            for j in 0..N_SYM {
+                if cubes[i].multiply(&cubes[j]) == solved {
+                    inverse[i] = j;
+                    break;
+                }
+            }
+        }
+
+        Symmetries { cubes, inverse }
+    })
+}
+
+/// Builds one of the four basic symmetries from its cubie arrays.
+fn basic_symmetry(
+    corners: [Corner; 8],
+    corner_orientations: [u8; 8],
+    edges: [Edge; 12],
+    edge_orientations: [u8; 12],
+) -> Cube {
+    Cube {
+        corners: CornerCubies {
+            permutation: corners,
+            orientation: corner_orientations,
+        },
+        edges: EdgeCubies {
+            permutation: edges,
+            orientation: edge_orientations,
+        },
+    }
+}
+
+/// All 48 symmetries as `Cube`s, in the order described at the top of this
+/// module.
+///
+/// # Return
+/// * `&'static [Cube]`
+pub fn symmetries() -> &'static [Cube] {
+    &symmetry_table().cubes
+}
+
+/// Finds the symmetry that undoes symmetry `s`.
+///
+/// # Parameters
+/// * `s` - A symmetry between 0 and 47.
+/// # Return
+/// * `usize` - The symmetry `t` with `s * t` the identity.
+pub fn inverse(s: usize) -> usize {
+    symmetry_table().inverse[s]
+}
+
+/// The symmetries that turn a cube whole, as the rotations x, y and z do.
+///
+/// Turning the whole cube leaves the pieces where they were against the
+/// centres, but gives the faces new names. Conjugating by one of these
+/// gives the cube with its faces renamed, so after x what was the front is
+/// now called the up face.
+///
+/// # Return
+/// * `[usize; 3]` - The symmetries of x, y and z, which turn the cube like
+///     R, U and F.
+pub fn rotations() -> [usize; 3] {
+    static ROTATIONS: OnceLock<[usize; 3]> = OnceLock::new();
+    *ROTATIONS.get_or_init(|| {
+        let turned = |turn: fn(&mut Cube)| {
+            let mut c = Cube::new();
+            turn(&mut c);
+            c
+        };
+        let (f, u, r, l) = (turned(Cube::f), turned(Cube::u), turned(Cube::r), turned(Cube::l));
+        // Each rotation is the symmetry that renames one face to another
+        // and leaves the face it turns like alone.
+        let find = |axis: &Cube, from: &Cube, to: &Cube| {
+            (0..N_SYM)
+                .find(|s| conjugate(axis, *s) == *axis && conjugate(from, *s) == *to)
+                .unwrap()
+        };
+        [find(&r, &f, &u), find(&u, &r, &f), find(&f, &l, &u)]
+    })
+}
+
+/// Conjugates a cube by a symmetry, giving `S^-1 * rubiks * S`.
+///
+/// This is the same scramble seen from another angle, or in a mirror. It
+/// takes just as many moves to solve.
+///
+/// # Parameters
+/// * `rubiks` - The `Cube` to conjugate.
+/// * `s` - A symmetry between 0 and 47.
+/// # Return
+/// * `Cube` - The conjugated `Cube`.
+pub fn conjugate(rubiks: &Cube, s: usize) -> Cube {
+    let table = symmetry_table();
+    table.cubes[table.inverse[s]].multiply(rubiks).multiply(&table.cubes[s])
+}
+
+/// Conjugates only the corners of a cube, giving the corners of
+/// `S^-1 * rubiks * S`. Used when building tables that only need corners.
+///
+/// # Parameters
+/// * `corners` - The corners to conjugate.
+/// * `s` - A symmetry between 0 and 47.
+/// # Return
+/// * `CornerCubies` - The conjugated corners.
+pub fn conjugate_corners(corners: &CornerCubies, s: usize) -> CornerCubies {
+    let table = symmetry_table();
+    let c = corner_cubies::multiply(&table.cubes[table.inverse[s]].corners, corners);
+    corner_cubies::multiply(&c, &table.cubes[s].corners)
+}
+
+/// Conjugates only the edges of a cube, giving the edges of
+/// `S^-1 * rubiks * S`. Used when building tables that only need edges.
+///
+/// # Parameters
+/// * `edges` - The edges to conjugate.
+/// * `s` - A symmetry between 0 and 47.
+/// # Return
+/// * `EdgeCubies` - The conjugated edges.
+pub fn conjugate_edges(edges: &EdgeCubies, s: usize) -> EdgeCubies {
+    let table = symmetry_table();
+    let c = edge_cubies::multiply(&table.cubes[table.inverse[s]].edges, edges);
+    edge_cubies::multiply(&c, &table.cubes[s].edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled() -> Cube {
+        let mut rubiks = Cube::new();
+        rubiks.r();
+        rubiks.u();
+        rubiks.f();
+        rubiks.f();
+        rubiks.l();
+        rubiks.d();
+        rubiks.b();
+        rubiks
+    }
+
+    #[test]
+    fn test_symmetry_inverses() {
+        let solved = Cube::new();
+        assert_eq!(symmetries()[0], solved);
+        for s in 0..N_SYM {
+            let t = inverse(s);
+            assert_eq!(symmetries()[s].multiply(&symmetries()[t]), solved);
+            assert_eq!(symmetries()[t].multiply(&symmetries()[s]), solved);
+        }
+    }
+
+    #[test]
+    fn test_conjugate_round_trip() {
+        let rubiks = scrambled();
+        for s in 0..N_SYM {
+            let c = conjugate(&conjugate(&rubiks, s), inverse(s));
+            assert_eq!(c, rubiks);
+        }
+        // Keeping the UD axis keeps G1, so the UD slice edges stay together.
+        let mut g1 = Cube::new();
+        g1.r();
+        g1.r();
+        g1.u();
+        for s in 0..N_SYM_D4H {
+            assert_eq!(conjugate(&g1, s).ud_slice(), 0);
+            assert_eq!(conjugate(&g1, s).edge_orientation(), 0);
+            assert_eq!(conjugate(&g1, s).corner_orientation(), 0);
+        }
+    }
+
+    #[test]
+    fn test_axes() {
+        let turns: [fn(&mut Cube); 3] = [Cube::u, Cube::f, Cube::r];
+        for a in 0..3 {
+            let mut c = Cube::new();
+            turns[a](&mut c);
+            // A quarter turn is only in G1 if it turns the U or D face.
+            let c = conjugate(&c, AXES[a]);
+            assert_eq!((c.corner_orientation(), c.edge_orientation(), c.ud_slice()), (0, 0, 0));
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Algorithms written the way cubers write them down, with more than plain
+//! moves:
+//!
+//! * `(R U R' U')3` - a group, done 3 times. `(R U)'` is the group undone.
+//! * `[R, U]` - a commutator, `R U R' U'`.
+//! * `[F: R U]` - a conjugate, `F R U F'`.
+//! * `// ...` - a comment, to the end of the line.
+//!
+//! These nest as deep as wanted. An `Algorithm` keeps the structure, so it
+//! prints back in the same compact form, and `expand` gives the plain moves.
+
+use std::fmt;
+use std::str::FromStr;
+
+use solver::notation::{read_move, unknown_move, ParseError, ParseErrorKind};
+use solver::{inverse_move, Moves};
+
+/// The most moves an algorithm may expand to. Anything longer is almost
+/// certainly a mistake, like a stray `1000`, and would take a long time to
+/// write out.
+pub const MAX_EXPANSION: usize = 10_000;
+
+/// An algorithm, as written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Algorithm {
+    /// A single move.
+    Move(Moves),
+    /// Algorithms done one after the other.
+    Sequence(Vec<Algorithm>),
+    /// `(A)n'` - `body` done `times` times, then undone if `inverse`.
+    Group {
+        body: Box<Algorithm>,
+        times: usize,
+        inverse: bool,
+    },
+    /// `[A, B]` - `A B A' B'`.
+    Commutator(Box<Algorithm>, Box<Algorithm>),
+    /// `[A: B]` - `A B A'`.
+    Conjugate(Box<Algorithm>, Box<Algorithm>),
+}
+
+impl Algorithm {
+    /// Reads an algorithm.
+    ///
+    /// # Parameters
+    /// * `input` - The algorithm as written.
+    /// # Returns
+    /// * `Result<Algorithm, ParseError>` - The algorithm, or where it could
+    ///     not be read. Algorithms longer than `MAX_EXPANSION` moves are
+    ///     refused.
+    pub fn parse(input: &str) -> Result<Algorithm, ParseError> {
+        let mut parser = Parser { input, pos: 0 };
+        let algorithm = parser.sequence()?;
+        if let Some(c) = parser.peek() {
+            let span = parser.pos..parser.pos + c.len_utf8();
+            return Err(ParseError::new(ParseErrorKind::Unexpected, input, span));
+        }
+        if algorithm.len() > MAX_EXPANSION {
+            return Err(ParseError::new(ParseErrorKind::TooLong, input, 0..input.len()));
+        }
+        Ok(algorithm)
+    }
+
+    /// The number of moves the algorithm expands to. Stops counting at
+    /// `usize::MAX`.
+    pub fn len(&self) -> usize {
+        match *self {
+            Algorithm::Move(_) => 1,
+            Algorithm::Sequence(ref items) => items.iter().fold(0, |sum, item| sum.saturating_add(item.len())),
+            Algorithm::Group { ref body, times, .. } => body.len().saturating_mul(times),
+            Algorithm::Commutator(ref a, ref b) => a.len().saturating_add(b.len()).saturating_mul(2),
+            Algorithm::Conjugate(ref a, ref b) => a.len().saturating_mul(2).saturating_add(b.len()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the algorithm out as plain moves.
+    ///
+    /// # Returns
+    /// * `Option<Vec<Moves>>` - The moves, or None if there would be more
+    ///     than `MAX_EXPANSION`.
+    pub fn expand(&self) -> Option<Vec<Moves>> {
+        if self.len() > MAX_EXPANSION {
+            return None;
+        }
+        let mut moves = Vec::with_capacity(self.len());
+        self.expand_into(&mut moves, false);
+        Some(moves)
+    }
+
+    /// Adds the moves of the algorithm, or of its inverse, to `moves`.
+    fn expand_into(&self, moves: &mut Vec<Moves>, inverse: bool) {
+        match *self {
+            Algorithm::Move(movement) => moves.push(if inverse { inverse_move(movement) } else { movement }),
+            Algorithm::Sequence(ref items) => {
+                if inverse {
+                    for item in items.iter().rev() {
+                        item.expand_into(moves, true);
+                    }
+                } else {
+                    for item in items.iter() {
+                        item.expand_into(moves, false);
+                    }
+                }
+            }
+            Algorithm::Group {
+                ref body,
+                times,
+                inverse: undone,
+            } => {
+                for _ in 0..times {
+                    body.expand_into(moves, inverse != undone);
+                }
+            }
+            Algorithm::Commutator(ref a, ref b) => {
+                // Undoing [A, B] is [B, A].
+                let (a, b) = if inverse { (b, a) } else { (a, b) };
+                a.expand_into(moves, false);
+                b.expand_into(moves, false);
+                a.expand_into(moves, true);
+                b.expand_into(moves, true);
+            }
+            Algorithm::Conjugate(ref a, ref b) => {
+                a.expand_into(moves, false);
+                b.expand_into(moves, inverse);
+                a.expand_into(moves, true);
+            }
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Algorithm, ParseError> {
+        Algorithm::parse(s)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    /// Writes the algorithm in the compact form it was read from, without
+    /// its comments.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Algorithm::Move(movement) => write!(f, "{}", movement),
+            Algorithm::Sequence(ref items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                Ok(())
+            }
+            Algorithm::Group {
+                ref body,
+                times,
+                inverse,
+            } => {
+                match **body {
+                    Algorithm::Commutator(..) | Algorithm::Conjugate(..) => write!(f, "{}", body)?,
+                    _ => write!(f, "({})", body)?,
+                }
+                if times != 1 {
+                    write!(f, "{}", times)?;
+                }
+                if inverse {
+                    write!(f, "'")?;
+                }
+                Ok(())
+            }
+            Algorithm::Commutator(ref a, ref b) => write!(f, "[{}, {}]", a, b),
+            Algorithm::Conjugate(ref a, ref b) => write!(f, "[{}: {}]", a, b),
+        }
+    }
+}
+
+/// Reads an algorithm from left to right.
+///
+/// # Variables
+/// * `input` - The algorithm as written.
+/// * `pos` - The byte being read.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// The next character that is not whitespace or in a comment.
+    fn peek(&mut self) -> Option<char> {
+        loop {
+            let rest = &self.input[self.pos..];
+            let c = rest.chars().next()?;
+            if c.is_whitespace() {
+                self.pos = self.pos + c.len_utf8();
+            } else if rest.starts_with("//") {
+                self.pos = self.pos + rest.find('\n').unwrap_or(rest.len());
+            } else {
+                return Some(c);
+            }
+        }
+    }
+
+    /// Reads algorithms up to the end of the input, or of the enclosing
+    /// brackets.
+    fn sequence(&mut self) -> Result<Algorithm, ParseError> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            let item = match c {
+                ')' | ']' | ',' | ':' => break,
+                '(' => self.group()?,
+                '[' => self.brackets()?,
+                _ => match read_move(self.input, self.pos)? {
+                    Some((movement, end)) => {
+                        self.pos = end;
+                        Algorithm::Move(movement)
+                    }
+                    None => {
+                        let ends = |c: char| c.is_whitespace() || "()[],:".contains(c);
+                        return Err(unknown_move(self.input, self.pos, ends));
+                    }
+                },
+            };
+            items.push(item);
+        }
+        Ok(Algorithm::Sequence(items))
+    }
+
+    /// Reads `(A)n'`.
+    fn group(&mut self) -> Result<Algorithm, ParseError> {
+        let start = self.pos;
+        self.pos = self.pos + 1;
+        let body = self.sequence()?;
+        self.close(start, ')')?;
+        self.suffix(start, body)
+    }
+
+    /// Reads `[A, B]` or `[A: B]`, and any suffix after them.
+    fn brackets(&mut self) -> Result<Algorithm, ParseError> {
+        let start = self.pos;
+        self.pos = self.pos + 1;
+        let a = Box::new(self.sequence()?);
+        let separator = self.peek();
+        if separator != Some(',') && separator != Some(':') {
+            return Err(self.unclosed_or_unexpected(start));
+        }
+        self.pos = self.pos + 1;
+        let b = Box::new(self.sequence()?);
+        self.close(start, ']')?;
+        let algorithm = if separator == Some(',') {
+            Algorithm::Commutator(a, b)
+        } else {
+            Algorithm::Conjugate(a, b)
+        };
+        self.suffix(start, algorithm)
+    }
+
+    /// Reads the bracket closing the one at `start`.
+    fn close(&mut self, start: usize, bracket: char) -> Result<(), ParseError> {
+        if self.peek() == Some(bracket) {
+            self.pos = self.pos + 1;
+            Ok(())
+        } else {
+            Err(self.unclosed_or_unexpected(start))
+        }
+    }
+
+    /// The error for something other than the bracket or separator wanted:
+    /// the bracket at `start` is unclosed if the input has run out, and
+    /// whatever is next is unexpected if it has not.
+    fn unclosed_or_unexpected(&mut self, start: usize) -> ParseError {
+        match self.peek() {
+            Some(c) => ParseError::new(ParseErrorKind::Unexpected, self.input, self.pos..self.pos + c.len_utf8()),
+            None => ParseError::new(ParseErrorKind::Unclosed, self.input, start..start + 1),
+        }
+    }
+
+    /// Reads how many times to do `body` and whether to undo it, both
+    /// written straight after the closing bracket.
+    fn suffix(&mut self, start: usize, body: Algorithm) -> Result<Algorithm, ParseError> {
+        let digits = self.input[self.pos..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.input.len() - self.pos);
+        let times = match digits {
+            0 => 1,
+            _ => match self.input[self.pos..self.pos + digits].parse::<usize>() {
+                Ok(times) if times > 0 => times,
+                _ => {
+                    let span = start..self.pos + digits;
+                    return Err(ParseError::new(ParseErrorKind::BadAmount, self.input, span));
+                }
+            },
+        };
+        self.pos = self.pos + digits;
+        let inverse = self.input[self.pos..].starts_with('\'');
+        if inverse {
+            self.pos = self.pos + 1;
+        }
+
+        let algorithm = match body {
+            Algorithm::Commutator(..) | Algorithm::Conjugate(..) if times == 1 && !inverse => body,
+            _ => Algorithm::Group {
+                body: Box::new(body),
+                times,
+                inverse,
+            },
+        };
+        if algorithm.len() > MAX_EXPANSION {
+            return Err(ParseError::new(ParseErrorKind::TooLong, self.input, start..self.pos));
+        }
+        Ok(algorithm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solver::notation::parse_moves;
+
+    fn expand(input: &str) -> Vec<Moves> {
+        Algorithm::parse(input).unwrap().expand().unwrap()
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("(R U R' U')3"), parse_moves("R U R' U' R U R' U' R U R' U'").unwrap());
+        assert_eq!(expand("[R, U]"), parse_moves("R U R' U'").unwrap());
+        assert_eq!(expand("[F: [R, U]]"), parse_moves("F R U R' U' F'").unwrap());
+        assert_eq!(expand("(R U2)'"), parse_moves("U2 R'").unwrap());
+        assert_eq!(expand("[R, U]'"), parse_moves("U R U' R'").unwrap());
+        assert_eq!(expand("[R: U]2'"), parse_moves("R U' R' R U' R'").unwrap());
+        assert_eq!(
+            expand("// Sexy move\nR U R' U' // twice\n(R U R' U')"),
+            parse_moves("R U R' U' R U R' U'").unwrap()
+        );
+        assert_eq!(expand("[[R: U], (D F)2]").len(), 14);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for text in ["(R U R' U')3", "[F: [R, U]]", "R (U D)2' [L, B]2 F", "[R U: [D', L2]]'"].iter() {
+            assert_eq!(Algorithm::parse(text).unwrap().to_string(), *text);
+        }
+        assert_eq!(Algorithm::parse("  R  // done\n").unwrap().to_string(), "R");
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = Algorithm::parse("(R U").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::Unclosed);
+        assert_eq!(e.span, 0..1);
+
+        let e = Algorithm::parse("[R U]").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::Unexpected);
+        assert_eq!(e.span, 4..5);
+
+        let e = Algorithm::parse("R)").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::Unexpected);
+
+        let e = Algorithm::parse("[R, Q]").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::UnknownMove);
+        assert_eq!(e.token, "Q");
+
+        let e = Algorithm::parse("R (U)0").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::BadAmount);
+
+        let e = Algorithm::parse("F ((((R U)100)100)100)100").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::TooLong);
+        assert_eq!(e.span, 4..17);
+        let e = Algorithm::parse("(R)99999999999999999999999").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::BadAmount);
+    }
+}
@@ -0,0 +1,164 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Control over a search while it runs: stopping it from another thread,
+//! giving it a deadline or a number of positions to visit, and hearing how
+//! far it has got.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use solver::Moves;
+
+/// Stops a search from another thread. Every clone shares the same flag, so
+/// keep one and hand a clone to the search.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Asks every search holding a clone of this token to stop. The search
+    /// notices within a few thousand positions.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a search has got.
+///
+/// # Variables
+/// * `phase` - The phase being searched, 1 or 2. The optimal search is not
+///     split into phases, and reports 0.
+/// * `depth` - The length of move list the phase is looking at.
+/// * `nodes` - Positions visited so far, over both phases and every thread.
+/// * `elapsed` - Time since the search started.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Progress {
+    pub phase: usize,
+    pub depth: usize,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+/// Where a search reports its progress.
+pub type ProgressSink = Box<dyn FnMut(&Progress) + Send>;
+
+/// Where a search that keeps looking for shorter solutions reports each one
+/// it finds.
+pub type ImprovementSink = Box<dyn FnMut(&[Moves]) + Send>;
+
+/// The things a caller can control about a search.
+///
+/// # Variables
+/// * `cancel` - Stops the search when cancelled.
+/// * `deadline` - When the search must stop, if limited.
+/// * `max_nodes` - How many positions the search may visit, over every
+///     thread, if limited.
+/// * `target_length` - Searches that keep looking for shorter solutions
+///     stop at the first one this short.
+/// * `progress` - Called when the search starts a new depth, and every so
+///     often while it searches one.
+/// * `improvement` - Called by searches that keep looking for shorter
+///     solutions with every solution shorter than the ones before it.
+pub struct SolverContext {
+    pub cancel: CancelToken,
+    pub deadline: Option<Instant>,
+    pub max_nodes: Option<u64>,
+    pub target_length: Option<usize>,
+    pub progress: Option<ProgressSink>,
+    pub improvement: Option<ImprovementSink>,
+}
+
+impl SolverContext {
+    /// A context that never stops the search and reports nothing.
+    pub fn new() -> SolverContext {
+        SolverContext {
+            cancel: CancelToken::new(),
+            deadline: None,
+            max_nodes: None,
+            target_length: None,
+            progress: None,
+            improvement: None,
+        }
+    }
+
+    pub fn with_cancel_token(mut self, cancel: CancelToken) -> SolverContext {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> SolverContext {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the search `timeout` from now.
+    pub fn with_timeout(self, timeout: Duration) -> SolverContext {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Stops the search once it has visited `nodes` positions. They are
+    /// counted a thousand or so at a time, so it may run a little over.
+    pub fn with_node_limit(mut self, nodes: u64) -> SolverContext {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    pub fn with_target_length(mut self, length: usize) -> SolverContext {
+        self.target_length = Some(length);
+        self
+    }
+
+    pub fn with_progress<F>(mut self, progress: F) -> SolverContext
+    where
+        F: FnMut(&Progress) + Send + 'static,
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    pub fn with_improvement<F>(mut self, improvement: F) -> SolverContext
+    where
+        F: FnMut(&[Moves]) + Send + 'static,
+    {
+        self.improvement = Some(Box::new(improvement));
+        self
+    }
+}
+
+impl Default for SolverContext {
+    fn default() -> SolverContext {
+        SolverContext::new()
+    }
+}
+
+impl fmt::Debug for SolverContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SolverContext")
+            .field("cancel", &self.cancel)
+            .field("deadline", &self.deadline)
+            .field("max_nodes", &self.max_nodes)
+            .field("target_length", &self.target_length)
+            .field("progress", &self.progress.is_some())
+            .field("improvement", &self.improvement.is_some())
+            .finish()
+    }
+}
@@ -12,10 +12,37 @@
 //! parts, each focusing on a different mathematical group to solve. Both phases
 //! use the same implamentation of IDA*, with different depths and goals.
 
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub use self::context::{CancelToken, Progress, ProgressSink, SolverContext};
+pub use self::notation::Notation;
+pub use self::result::{simplify, SolveError, SolveResult};
+
 use physical;
 
+pub mod algorithm;
+pub mod context;
+pub mod move_tables;
+pub mod notation;
+pub mod optimal;
+pub mod parallel;
+pub mod pruning_tables;
+mod result;
+pub mod scramble;
+pub mod symmetry_tables;
+pub mod table_file;
+#[cfg(test)]
+mod test_util;
+
 /// All the avaliable moves you can perfom on a rubiks cube. x3 is an
 /// anti-clockwise movement.
+///
+/// The solver only ever uses the 18 face turns. After them come the slice
+/// moves, the whole cube rotations and the wide moves, for writing and
+/// checking algorithms. Every layer has its three moves in a row, so
+/// `movement as usize / 3` is the layer and `% 3` the amount of turn.
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub enum Moves {
     F1,
@@ -36,186 +63,834 @@ pub enum Moves {
     R1,
     R2,
     R3,
+    M1,
+    M2,
+    M3,
+    E1,
+    E2,
+    E3,
+    S1,
+    S2,
+    S3,
+    X1,
+    X2,
+    X3,
+    Y1,
+    Y2,
+    Y3,
+    Z1,
+    Z2,
+    Z3,
+    Fw1,
+    Fw2,
+    Fw3,
+    Bw1,
+    Bw2,
+    Bw3,
+    Uw1,
+    Uw2,
+    Uw3,
+    Dw1,
+    Dw2,
+    Dw3,
+    Lw1,
+    Lw2,
+    Lw3,
+    Rw1,
+    Rw2,
+    Rw3,
     NONE,
 }
 
 /// Finds the solution of a rubiks cube in under 30 moves. Uses two IDA*
 /// searches.
 ///
+/// Phase one takes the cube into the G1 subgroup with any of the 18 moves,
+/// then phase two solves it with only the moves that keep it in G1.
+///
 /// # Parameters
-/// * `rubiks` - A `Cube` struct that we are trying to solve. Is mutable
-///     and a reference so other functions can use it from main.
+/// * `rubiks` - A `Cube` struct that we are trying to solve.
 /// # Returns
-/// * `&'static str` - Returns move list.
-pub fn complete_search(rubiks: &mut physical::Cube) -> String{
-    let a = Vec::new();
-    //let b = Vec::new();
-    let mut c = rubiks.clone();
-    //println!("We got to the cloning.");///RM
-    println!("Phase one starting");
-    let g1_state_move_list = search(
-        &mut c,
-        a,
-        MAX_PHASE_ONE_DEPTH,
-        phase_one_subgoal,
-        &PHASE_ONE_MOVE_LIST,
-    );
-    return format!("Phase1 move list: {:?}", &g1_state_move_list[..]);
-    
-    //println!("We State Move list complete.");///RM
-    /*
-    let pristine_state_move_list = search(
-        &mut c,
-        b,
-        MAX_PHASE_TWO_DEPTH,
-        phase_two_subgoal,
-        &PHASE_TWO_MOVE_LIST,
-    );
-
-    return format!(
-        "Move list: {:?}",
-        [&g1_state_move_list[..], &pristine_state_move_list[..]].concat()
-    );*/
+/// * `Result<SolveResult, SolveError>` - The moves of both phases, or why
+///     there are none.
+pub fn complete_search(rubiks: &physical::Cube) -> Result<SolveResult, SolveError> {
+    complete_search_with_context(rubiks, SolverContext::new())
 }
 
-/// Checks if the conditions for a G1 state cube have been achieved.
+/// Finds a solution like `complete_search`, but can be stopped from another
+/// thread or by a deadline, and reports its progress as it goes. Both phases
+/// check the context every few thousand positions.
 ///
 /// # Parameters
-/// * `rubiks` - A `Cube` type object that we are checking for G1 state.
+/// * `rubiks` - A `Cube` struct that we are trying to solve.
+/// * `context` - When to stop, and where to report progress.
 /// # Returns
-/// * `bool` - True or false depending if the cube is in a G1 state.
-fn phase_one_subgoal(rubiks: physical::Cube) -> bool {
-    (rubiks.corner_orientation == 0) && (rubiks.edge_orientation == 0) && (rubiks.ud_slice == 0)
+/// * `Result<SolveResult, SolveError>` - The moves of both phases, or why
+///     there are none.
+pub fn complete_search_with_context(
+    rubiks: &physical::Cube,
+    context: SolverContext,
+) -> Result<SolveResult, SolveError> {
+    rubiks.check_solvable()?;
+    let mut tracker = Tracker::from_context(context);
+
+    let phase_one = Phase::one();
+    let g1_state_move_list = search_phase(&phase_one, phase_one.coordinates(rubiks), &mut tracker)?;
+
+    // The phase two coordinates are only defined once we are in G1, so we
+    // take the actual cube there before reading them.
+    let mut c = *rubiks;
+    for movement in g1_state_move_list.iter() {
+        c = do_move(c, *movement);
+    }
+
+    let phase_two = Phase::two();
+    let pristine_state_move_list = search_phase(&phase_two, phase_two.coordinates(&c), &mut tracker)?;
+
+    Ok(SolveResult {
+        moves: result::simplify(&[&g1_state_move_list[..], &pristine_state_move_list[..]].concat()),
+        phase_one: g1_state_move_list,
+        phase_two: pristine_state_move_list,
+        nodes: tracker.nodes,
+        elapsed: tracker.start.elapsed(),
+    })
 }
 
-/// Checks if the conditions for a pristine state cube have been achieved.
+/// Searches one phase up to its maximum depth, turning a failure into the
+/// reason for it.
+fn search_phase(
+    phase: &Phase,
+    coordinates: [usize; 3],
+    tracker: &mut Tracker,
+) -> Result<Vec<Moves>, SolveError> {
+    match search_up_to(phase, coordinates, phase.max_depth, Moves::NONE, tracker) {
+        Some(moves) => Ok(moves),
+        None => Err(tracker.stopped.unwrap_or(SolveError::DepthExhausted {
+            phase: phase.number,
+            max_depth: phase.max_depth,
+        })),
+    }
+}
+
+/// Finds a solution like `complete_search`, then keeps going to find
+/// shorter ones.
+///
+/// Every phase one solution is tried in order of length, each followed by
+/// the shortest phase two that would beat the best total so far. The search
+/// ends when the context stops it, when a solution of the context's target
+/// length is found, or when phase one alone is as long as the best solution.
 ///
 /// # Parameters
-/// * `rubiks` - A `Cube` type object that we are checking for a pristine state.
+/// * `rubiks` - A `Cube` struct that we are trying to solve.
+/// * `context` - When to stop looking, where to report progress, and where
+///     to report every solution shorter than the ones before it.
 /// # Returns
-/// * `bool` - True or false depending if the cube is in a pristine state.
-fn phase_two_subgoal(rubiks: physical::Cube) -> bool {
-    (rubiks.corner_permutation == 0)
-        && (rubiks.phase_two_edge_permutation == 0)
-        && (rubiks.ud_sorted_slice == 0)
+/// * `Result<SolveResult, SolveError>` - The shortest solution found, even
+///     if the context stopped the search after it. Otherwise why there is
+///     none: the cube cannot be solved, or the context stopped the search
+///     before any solution was found.
+pub fn complete_search_with_budget(
+    rubiks: &physical::Cube,
+    mut context: SolverContext,
+) -> Result<SolveResult, SolveError> {
+    rubiks.check_solvable()?;
+    let target_length = context.target_length.unwrap_or(0);
+    let mut on_improvement = context.improvement.take();
+    let two_phase = TwoPhase::new(*rubiks);
+    let best = Best::new();
+    let mut tracker = Tracker::from_context(context);
+
+    let mut depth = two_phase.distance;
+    while best.can_improve(depth, two_phase.phase_one.max_depth) {
+        let stopped = two_phase.try_depth(
+            depth,
+            &PHASE_ONE_MOVE_LIST,
+            &best,
+            &mut tracker,
+            &mut |phase_one, phase_two| {
+                if best.offer(phase_one, phase_two) {
+                    if let Some(ref mut report) = on_improvement {
+                        report(&[phase_one, phase_two].concat());
+                    }
+                }
+                phase_one.len() + phase_two.len() <= target_length
+            },
+        );
+        if stopped {
+            break;
+        }
+        depth = depth + 1;
+    }
+
+    best.result(tracker.nodes, tracker.start.elapsed())
+        .ok_or_else(|| tracker.stopped.unwrap_or(two_phase.depth_exhausted()))
+}
+
+/// The shortest solution found so far, shared by every thread of a search.
+///
+/// # Variables
+/// * `length` - The length of `moves`, or `usize::MAX` before any solution
+///     has been found. Kept apart so it can be read without locking.
+/// * `moves` - The shortest solution, split into its two phases.
+struct Best {
+    length: AtomicUsize,
+    moves: Mutex<(Vec<Moves>, Vec<Moves>)>,
+}
+
+impl Best {
+    fn new() -> Best {
+        Best {
+            length: AtomicUsize::new(usize::MAX),
+            moves: Mutex::new((Vec::new(), Vec::new())),
+        }
+    }
+
+    /// The length of the shortest solution, if there is one.
+    fn length(&self) -> Option<usize> {
+        match self.length.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            length => Some(length),
+        }
+    }
+
+    /// Keeps a solution if it is shorter than the best so far.
+    ///
+    /// # Parameters
+    /// * `phase_one` - The first phase of the solution.
+    /// * `phase_two` - The second phase of the solution.
+    /// # Returns
+    /// * `bool` - True if it was kept.
+    fn offer(&self, phase_one: &[Moves], phase_two: &[Moves]) -> bool {
+        let mut moves = self.moves.lock().unwrap();
+        let length = phase_one.len() + phase_two.len();
+        if length >= self.length.load(Ordering::Relaxed) {
+            return false;
+        }
+        *moves = (phase_one.to_vec(), phase_two.to_vec());
+        self.length.store(length, Ordering::Relaxed);
+        true
+    }
+
+    /// Whether a phase one of `depth` moves could still lead to a better
+    /// solution. Before there is any solution phase one keeps to its usual
+    /// limit, after that it may run on, as a longer phase one can leave a
+    /// much shorter phase two.
+    fn can_improve(&self, depth: usize, max_depth: usize) -> bool {
+        match self.length() {
+            Some(length) => depth < length,
+            None => depth <= max_depth,
+        }
+    }
+
+    /// The shortest solution as a `SolveResult`, if there is one.
+    ///
+    /// # Parameters
+    /// * `nodes` - Positions visited by the search.
+    /// * `elapsed` - How long the search took.
+    /// # Returns
+    /// * `Option<SolveResult>`
+    fn result(&self, nodes: u64, elapsed: Duration) -> Option<SolveResult> {
+        self.length()?;
+        let (phase_one, phase_two) = self.moves.lock().unwrap().clone();
+        Some(SolveResult {
+            moves: result::simplify(&[&phase_one[..], &phase_two[..]].concat()),
+            phase_one,
+            phase_two,
+            nodes,
+            elapsed,
+        })
+    }
+}
+
+/// A cube set up for the budgeted two-phase search.
+///
+/// # Variables
+/// * `rubiks` - The `Cube` being solved.
+/// * `phase_one` - Phase one.
+/// * `phase_two` - Phase two.
+/// * `coordinates` - The phase one coordinates of `rubiks`.
+/// * `distance` - The fewest moves phase one can take.
+/// * `start` - The phase two coordinates of `rubiks`. Only the corners and
+///     sorted slice mean anything until the cube is in G1.
+struct TwoPhase {
+    rubiks: physical::Cube,
+    phase_one: Phase,
+    phase_two: Phase,
+    coordinates: [usize; 3],
+    distance: usize,
+    start: [usize; 3],
+}
+
+impl TwoPhase {
+    fn new(rubiks: physical::Cube) -> TwoPhase {
+        let phase_one = Phase::one();
+        let phase_two = Phase::two();
+        let coordinates = phase_one.coordinates(&rubiks);
+        TwoPhase {
+            rubiks,
+            distance: phase_one.distance(coordinates),
+            start: phase_two.coordinates(&rubiks),
+            coordinates,
+            phase_one,
+            phase_two,
+        }
+    }
+
+    /// Why a search that ran to the end found no solution: there was none
+    /// within phase one's maximum depth.
+    fn depth_exhausted(&self) -> SolveError {
+        SolveError::DepthExhausted {
+            phase: self.phase_one.number,
+            max_depth: self.phase_one.max_depth,
+        }
+    }
+
+    /// Tries every phase one solution of exactly `depth` moves that starts
+    /// with one of `first_moves`, each followed by the shortest phase two
+    /// that would beat `best`.
+    ///
+    /// # Parameters
+    /// * `depth` - The length of phase one.
+    /// * `first_moves` - The moves phase one may start with.
+    /// * `best` - The best solution so far.
+    /// * `tracker` - Counts positions and stops the search when out of
+    ///     budget.
+    /// * `found` - Called with the two phases of every solution that beats
+    ///     `best` at the time it is found. Returns true to stop the search.
+    /// # Returns
+    /// * `bool` - True if the search was stopped, by `found` or the budget.
+    fn try_depth(
+        &self,
+        depth: usize,
+        first_moves: &[Moves],
+        best: &Best,
+        tracker: &mut Tracker,
+        found: &mut dyn FnMut(&[Moves], &[Moves]) -> bool,
+    ) -> bool {
+        if tracker.start_depth(1, depth) {
+            return true;
+        }
+        let mut visit = |prefix: &[Moves], tracker: &mut Tracker| self.finish(prefix, best, tracker, found);
+        if depth == 0 {
+            return self.coordinates == [0, 0, 0] && visit(&[], tracker);
+        }
+
+        let mut move_list = Vec::new();
+        for movement in first_moves.iter() {
+            let next = self.phase_one.do_move(self.coordinates, *movement);
+            let next_distance = self.phase_one.next_distance(self.distance, next);
+            if next_distance >= depth {
+                continue;
+            }
+            move_list.push(*movement);
+            if tree_search(
+                &self.phase_one,
+                next,
+                next_distance,
+                depth - 1,
+                *movement,
+                &mut move_list,
+                tracker,
+                &mut visit,
+            ) {
+                return true;
+            }
+            move_list.pop();
+        }
+        tracker.exhausted
+    }
+
+    /// Looks for a phase two after a phase one solution.
+    ///
+    /// # Returns
+    /// * `bool` - True if the search should stop.
+    fn finish(
+        &self,
+        prefix: &[Moves],
+        best: &Best,
+        tracker: &mut Tracker,
+        found: &mut dyn FnMut(&[Moves], &[Moves]) -> bool,
+    ) -> bool {
+        // A phase one ending in a phase two move was already in G1 one move
+        // earlier, and has been tried.
+        let last_move = *prefix.last().unwrap_or(&Moves::NONE);
+        if PHASE_TWO_MOVE_LIST.contains(&last_move) {
+            return false;
+        }
+        let max_depth = match best.length() {
+            Some(length) if length <= prefix.len() + 1 => return false,
+            Some(length) => length - prefix.len() - 1,
+            None => self.phase_two.max_depth,
+        };
+
+        // The corners and sorted slice can be followed through the move
+        // tables, which is far cheaper than turning a `Cube` and rules most
+        // phase one solutions out.
+        let (mut corners, mut sorted_slice) = (self.start[0], self.start[2]);
+        for movement in prefix.iter() {
+            corners = self.phase_two.tables[0].do_move(corners, *movement);
+            sorted_slice = self.phase_two.tables[2].do_move(sorted_slice, *movement);
+        }
+        if self.phase_two.extra_bound([corners, 0, sorted_slice]) > max_depth {
+            return false;
+        }
+
+        // Only the edges are left to find, so only they are turned.
+        let mut edges = self.rubiks.edges;
+        for movement in prefix.iter() {
+            edges = physical::edge_cubies::multiply(&edges, &physical::face_turns()[*movement as usize].edges);
+        }
+        let c = physical::Cube {
+            corners: self.rubiks.corners,
+            edges,
+        };
+        let coordinates = [corners, c.phase_two_edge_permutation() as usize, sorted_slice];
+        let rest = search_up_to(&self.phase_two, coordinates, max_depth, last_move, tracker);
+        tracker.phase = 1;
+        tracker.depth = prefix.len();
+        if let Some(rest) = rest {
+            if found(prefix, &rest) {
+                return true;
+            }
+        }
+        tracker.exhausted
+    }
+}
+
+/// Counts the positions a search visits and stops it when it has used up
+/// its budget. Trackers made with `share` stop together, and count their
+/// positions towards the same budget.
+///
+/// # Variables
+/// * `nodes` - Positions visited so far by this tracker.
+/// * `max_nodes` - How many positions may be visited, if limited.
+/// * `deadline` - When the search must stop, if limited.
+/// * `shared_nodes` - Positions visited by every sharing tracker, counted
+///     in batches.
+/// * `stop` - Set by any sharing tracker to stop them all.
+/// * `cancel` - The caller's token, if the search was given one.
+/// * `progress` - Where to report progress, if anywhere.
+/// * `start` - When the search started.
+/// * `phase` - The phase being searched, for reporting.
+/// * `depth` - The depth being searched, for reporting.
+/// * `stopped` - Why the caller's context stopped the search, if it did
+///     and this tracker was the one to notice.
+/// * `exhausted` - Set once the budget has run out.
+struct Tracker {
+    nodes: u64,
+    max_nodes: Option<u64>,
+    deadline: Option<Instant>,
+    shared_nodes: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    cancel: Option<CancelToken>,
+    progress: Option<ProgressSink>,
+    start: Instant,
+    phase: usize,
+    depth: usize,
+    stopped: Option<SolveError>,
+    exhausted: bool,
+}
+
+/// How many positions a tracker visits between checks of the clock and the
+/// shared state.
+const TRACKER_BATCH: u64 = 1024;
+
+/// How many positions a tracker visits between progress reports.
+const PROGRESS_BATCH: u64 = 1 << 18;
+
+impl Tracker {
+    fn new(max_nodes: Option<u64>, time: Option<Duration>) -> Tracker {
+        Tracker {
+            nodes: 0,
+            max_nodes,
+            deadline: time.map(|t| Instant::now() + t),
+            shared_nodes: Arc::new(AtomicU64::new(0)),
+            stop: Arc::new(AtomicBool::new(false)),
+            cancel: None,
+            progress: None,
+            start: Instant::now(),
+            phase: 1,
+            depth: 0,
+            stopped: None,
+            exhausted: false,
+        }
+    }
+
+    /// A tracker that stops and reports as `context` asks.
+    fn from_context(context: SolverContext) -> Tracker {
+        let mut tracker = Tracker::new(context.max_nodes, None);
+        tracker.deadline = context.deadline;
+        tracker.cancel = Some(context.cancel);
+        tracker.progress = context.progress;
+        tracker
+    }
+
+    /// A new tracker on the same budget, for another thread. Only the
+    /// original reports progress, counting the positions of every thread.
+    fn share(&self) -> Tracker {
+        Tracker {
+            nodes: 0,
+            max_nodes: self.max_nodes,
+            deadline: self.deadline,
+            shared_nodes: self.shared_nodes.clone(),
+            stop: self.stop.clone(),
+            cancel: self.cancel.clone(),
+            progress: None,
+            start: self.start,
+            phase: self.phase,
+            depth: self.depth,
+            stopped: None,
+            exhausted: false,
+        }
+    }
+
+    /// Stops every tracker sharing this one's budget.
+    fn stop_all(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.exhausted = true;
+    }
+
+    /// Notes that a search has started on a new depth, reports it, and
+    /// checks whether the search should stop.
+    fn start_depth(&mut self, phase: usize, depth: usize) -> bool {
+        self.phase = phase;
+        self.depth = depth;
+        self.report();
+        self.check();
+        self.exhausted
+    }
+
+    fn report(&mut self) {
+        // The shared count is only added to in batches, so add on what
+        // this tracker has visited since its last one.
+        let progress = Progress {
+            phase: self.phase,
+            depth: self.depth,
+            nodes: self.shared_nodes.load(Ordering::Relaxed) + self.nodes % TRACKER_BATCH,
+            elapsed: self.start.elapsed(),
+        };
+        if let Some(ref mut report) = self.progress {
+            report(&progress);
+        }
+    }
+
+    /// Counts one more position, and checks whether the search should stop.
+    #[inline]
+    fn visit(&mut self) -> bool {
+        self.nodes = self.nodes + 1;
+        // Reading the clock and the shared state is slow, so only do it
+        // every so often.
+        if self.nodes % TRACKER_BATCH == 0 {
+            self.shared_nodes.fetch_add(TRACKER_BATCH, Ordering::Relaxed);
+            self.check();
+            if self.nodes % PROGRESS_BATCH == 0 {
+                self.report();
+            }
+        }
+        self.exhausted
+    }
+
+    /// Checks the budget, the deadline and the stop flags.
+    fn check(&mut self) {
+        let total = self.shared_nodes.load(Ordering::Relaxed);
+        let out_of_nodes = self.max_nodes.map_or(false, |max_nodes| total > max_nodes);
+        let out_of_time = self.deadline.map_or(false, |deadline| Instant::now() >= deadline);
+        let cancelled = self.cancel.as_ref().map_or(false, |cancel| cancel.is_cancelled());
+        if cancelled {
+            self.stopped = Some(SolveError::Cancelled);
+        } else if out_of_time {
+            self.stopped = Some(SolveError::TimedOut);
+        } else if out_of_nodes {
+            self.stopped = Some(SolveError::NodeLimit);
+        }
+        if out_of_nodes || out_of_time || cancelled {
+            self.stop_all();
+        } else if self.stop.load(Ordering::Relaxed) {
+            self.exhausted = true;
+        }
+    }
 }
 
-/// Basic IDA* search using the movements of the rubiks as a faux tree.
+/// One phase of the two-phase algorithm.
+///
+/// Each phase is searched on three coordinates and is solved once all of
+/// them are 0. The first two are bounded together by a symmetry pruning
+/// table that gives the exact distance for them, and a phase can have a
+/// second table on the first and third coordinates.
+///
+/// # Variables
+/// * `number` - 1 or 2.
+/// * `tables` - The `MoveTable` of each coordinate.
+/// * `pruning` - The `SymPruningTable` of the phase.
+/// * `extra` - A `PruningTable` on the first and third coordinates, if any.
+/// * `split` - Turns the coordinates into the two looked up in `pruning`.
+/// * `coordinates` - Reads each coordinate off a `Cube`.
+/// * `moves` - What rubiks cube moves are valid in this phase.
+/// * `max_depth` - The longest move list this phase will look for.
+pub struct Phase {
+    number: usize,
+    tables: [&'static move_tables::MoveTable; 3],
+    pruning: &'static pruning_tables::SymPruningTable,
+    extra: Option<&'static pruning_tables::PruningTable>,
+    split: fn([usize; 3]) -> (usize, usize),
+    coordinates: [fn(&physical::Cube) -> usize; 3],
+    moves: &'static [Moves],
+    max_depth: usize,
+}
+
+impl Phase {
+    /// Phase one, taking the cube into G1. Searched on the corner twist, the
+    /// edge flip and the UD slice, with the flip and slice joined into one
+    /// symmetry-reduced coordinate for pruning.
+    pub fn one() -> Phase {
+        let tables = move_tables::tables();
+        let pruning = pruning_tables::tables();
+        Phase {
+            number: 1,
+            tables: [&tables.twist, &tables.flip, &tables.slice],
+            pruning: &pruning.flip_slice_twist,
+            extra: None,
+            split: |c| (c[2] * move_tables::N_FLIP + c[1], c[0]),
+            coordinates: [
+                |c| c.corner_orientation() as usize,
+                |c| c.edge_orientation() as usize,
+                |c| c.ud_slice() as usize,
+            ],
+            moves: &PHASE_ONE_MOVE_LIST,
+            max_depth: MAX_PHASE_ONE_DEPTH,
+        }
+    }
+
+    /// Phase two, solving a G1 cube. Searched on the corner permutation, the
+    /// U and D edge permutation and the sorted UD slice.
+    pub fn two() -> Phase {
+        let tables = move_tables::tables();
+        let pruning = pruning_tables::tables();
+        Phase {
+            number: 2,
+            tables: [
+                &tables.corner_permutation,
+                &tables.edge_permutation,
+                &tables.sorted_slice,
+            ],
+            pruning: &pruning.corner_edge_permutation,
+            extra: Some(&pruning.corner_sorted_slice),
+            split: |c| (c[0], c[1]),
+            coordinates: [
+                |c| c.corner_permutation() as usize,
+                |c| c.phase_two_edge_permutation() as usize,
+                |c| c.ud_sorted_slice() as usize,
+            ],
+            moves: &PHASE_TWO_MOVE_LIST,
+            max_depth: MAX_PHASE_TWO_DEPTH,
+        }
+    }
+
+    /// Reads the coordinates of this phase off a `Cube`.
+    ///
+    /// # Parameters
+    /// * `rubiks` - The `Cube` to read.
+    /// # Returns
+    /// * `[usize; 3]` - The three coordinates this phase is searched on.
+    pub fn coordinates(&self, rubiks: &physical::Cube) -> [usize; 3] {
+        [
+            (self.coordinates[0])(rubiks),
+            (self.coordinates[1])(rubiks),
+            (self.coordinates[2])(rubiks),
+        ]
+    }
+
+    /// Looks up the coordinates reached by doing `movement`.
+    ///
+    /// # Parameters
+    /// * `coordinates` - The coordinates before the move.
+    /// * `movement` - The `Moves` to do.
+    /// # Returns
+    /// * `[usize; 3]` - The coordinates after the move.
+    #[inline]
+    fn do_move(&self, coordinates: [usize; 3], movement: Moves) -> [usize; 3] {
+        [
+            self.tables[0].do_move(coordinates[0], movement),
+            self.tables[1].do_move(coordinates[1], movement),
+            self.tables[2].do_move(coordinates[2], movement),
+        ]
+    }
+
+    /// The exact number of moves needed to solve the first two coordinates.
+    ///
+    /// # Parameters
+    /// * `coordinates` - The coordinates to measure.
+    /// # Returns
+    /// * `usize` - The moves needed to solve the first two coordinates.
+    fn distance(&self, coordinates: [usize; 3]) -> usize {
+        let (a, b) = (self.split)(coordinates);
+        self.pruning.distance(a, b)
+    }
+
+    /// The distance of the first two coordinates after a move, given the
+    /// distance before it.
+    #[inline]
+    fn next_distance(&self, distance: usize, coordinates: [usize; 3]) -> usize {
+        let (a, b) = (self.split)(coordinates);
+        self.pruning.next_distance(distance, a, b)
+    }
+
+    /// The bound from the extra pruning table, or 0 if there is none.
+    #[inline]
+    fn extra_bound(&self, coordinates: [usize; 3]) -> usize {
+        match self.extra {
+            Some(table) => table.distance(coordinates[0], coordinates[2]),
+            None => 0,
+        }
+    }
+}
+
+/// IDA* search using the movements of the rubiks as a faux tree. Each
+/// iteration allows one more move than the last, starting from the pruning
+/// table bound of the starting coordinates.
 ///
 /// # Parameters
-/// * `rubiks` - A mutable reference to the `Cube` we are solving.
-/// * `move_list` - A `Vec<Moves>` of the current `Moves` done so far.
-/// * `max_depth` - A `usize` describing the maximum length the search should
-///     bother with.
-/// * `subgoal` - A function that tests the a `Cube` for a solution state.
-/// * `whole_move_list` - What rubiks cube moves are valid in the current phase
-///     of the search.
+/// * `phase` - The `Phase` we are searching.
+/// * `coordinates` - The coordinates of the cube at the start of the phase.
 /// # Returns
 /// * `Vec<Moves>` - The moves required to get from the input cube to a solution
-///     state.
-pub fn search(
-    rubiks: &mut physical::Cube,
-    move_list: Vec<Moves>,
+///     state. Empty if there is none within the phase's maximum depth.
+pub fn search(phase: &Phase, coordinates: [usize; 3]) -> Vec<Moves> {
+    search_with_context(phase, coordinates, SolverContext::new()).unwrap_or_default()
+}
+
+/// Like `search`, but can be stopped and reports its progress.
+///
+/// # Parameters
+/// * `phase` - The `Phase` we are searching.
+/// * `coordinates` - The coordinates of the cube at the start of the phase.
+/// * `context` - When to stop, and where to report progress.
+/// # Returns
+/// * `Result<Vec<Moves>, SolveError>` - The moves to a solution state, or
+///     why the search ended without one.
+pub fn search_with_context(
+    phase: &Phase,
+    coordinates: [usize; 3],
+    context: SolverContext,
+) -> Result<Vec<Moves>, SolveError> {
+    let mut tracker = Tracker::from_context(context);
+    search_phase(phase, coordinates, &mut tracker)
+}
+
+/// IDA* search that gives up past `max_depth` moves.
+///
+/// # Parameters
+/// * `phase` - The `Phase` we are searching.
+/// * `coordinates` - The coordinates of the cube at the start of the phase.
+/// * `max_depth` - The longest move list to look for.
+/// * `last_move` - The move done just before this phase, which the first
+///     move must be able to follow.
+/// * `tracker` - Counts positions and stops the search when out of budget.
+/// # Returns
+/// * `Option<Vec<Moves>>` - The shortest solution, if there is one within
+///     `max_depth` moves and the budget.
+fn search_up_to(
+    phase: &Phase,
+    coordinates: [usize; 3],
     max_depth: usize,
-    subgoal: fn(physical::Cube) -> bool,
-    whole_move_list: &[Moves],
-) -> Vec<Moves> {
-    let mut results: bool = false;
-    let mut c: physical::Cube = rubiks.clone();
+    last_move: Moves,
+    tracker: &mut Tracker,
+) -> Option<Vec<Moves>> {
+    let distance = phase.distance(coordinates);
+    let lower_bound = if distance > phase.extra_bound(coordinates) {
+        distance
+    } else {
+        phase.extra_bound(coordinates)
+    };
+
     let mut solution: Vec<Moves> = Vec::new();
-    if subgoal(rubiks.clone()) {
-        results = true;
-    }
-    for i in 0..max_depth {
-        //println!("{} out of {}", i, max_depth);
-        tree_search(
-            rubiks,
-            &mut c,
-            i,
-            move_list.clone(),
-            &mut results,
+    for depth in lower_bound..(max_depth + 1) {
+        if tracker.start_depth(phase.number, depth) {
+            return None;
+        }
+        let found = tree_search(
+            phase,
+            coordinates,
+            distance,
+            depth,
+            last_move,
             &mut solution,
-            whole_move_list,
-            subgoal,
+            tracker,
+            &mut |_, _| true,
         );
-        if results == true {
-            break;
-        };
+        if tracker.exhausted {
+            return None;
+        }
+        if found {
+            return Some(solution);
+        }
     }
-    solution
+    None
 }
 
 /// The actual tree search used in the IDA* algorithim.
 ///
 /// # Parameters
-/// * `rubiks` - A `Cube` that we are trying to solve. Note this is before any
-///     recursion occurs and this only changes if a solution is found.
-/// * `dummy_rubiks` - A `Cube` we pass between recursions that reflects the
-///     current state of the cube at any point in the tree. Upon a solution
-///     being found we set `rubiks` to this.
-/// * `move_list` - The current moves done going down the tree.
-/// * `found` - Mutable `bool` signialling if a solutions has been found yet
-///     or not. If a solution has been found we break out of any loops and
-///     recursions.
-/// * `final_list` - The final `Vec<Moves>` required to get the original `Cube`
-///     to the solution state.
-/// * `whole_move_list` - The current set of `Moves` that can be taken.
-/// * `subgoal` - A function that tests to see if the correct permutation of
-///     `Moves` have been performed to solve the `Cube`.
+/// * `phase` - The `Phase` we are searching.
+/// * `coordinates` - The coordinates of the cube at this point in the tree.
+/// * `distance` - The exact distance of the first two coordinates.
+/// * `depth` - How many more moves we may do.
+/// * `last_move` - The move that got us here, or `Moves::NONE`.
+/// * `move_list` - The current moves done going down the tree. When a
+///     solution is found this is left holding it.
+/// * `tracker` - Counts positions and stops the search when out of budget.
+/// * `visit` - Called with every solution exactly `depth` moves long.
+///     Returns true to stop the search there.
+/// # Returns
+/// * `bool` - True if the search was stopped, by `visit` or the budget.
+#[allow(clippy::too_many_arguments)]
 fn tree_search(
-    rubiks: &mut physical::Cube,
-    dummy_rubiks: &mut physical::Cube,
+    phase: &Phase,
+    coordinates: [usize; 3],
+    distance: usize,
     depth: usize,
-    move_list: Vec<Moves>,
-    found: &mut bool,
-    final_list: &mut Vec<Moves>,
-    whole_move_list: &[Moves],
-    subgoal: fn(physical::Cube) -> bool,
-) {
-    if depth > 0 && *found == false {
-        for movement in whole_move_list.iter() {
-            if *found == false {
-                let last_move: Moves;
-                if move_list.len() != 0 {
-                    last_move = *move_list.last().unwrap();
-                } else {
-                    last_move = Moves::NONE;
-                }
+    last_move: Moves,
+    move_list: &mut Vec<Moves>,
+    tracker: &mut Tracker,
+    visit: &mut dyn FnMut(&[Moves], &mut Tracker) -> bool,
+) -> bool {
+    if tracker.visit() {
+        return true;
+    }
+    if depth == 0 {
+        return coordinates == [0, 0, 0] && visit(move_list, tracker);
+    }
 
-                if *movement != last_move
-                    && *movement != opposite_move(last_move)
-                    && *movement != cannot_follow(last_move)
-                {
-                    let mut current_list = move_list.clone();
-                    let mut c = dummy_rubiks.clone();
-                    c = do_move(c, *movement);
-                    current_list.push(*movement);
-
-                    if subgoal(c) {
-                        *found = true;
-                        *final_list = current_list.clone();
-                        *rubiks = c.clone();
-                        break;
-                    } else {
-                        tree_search(
-                            &mut *rubiks,
-                            &mut c,
-                            depth - 1,
-                            current_list.clone(),
-                            &mut *found,
-                            &mut *final_list,
-                            whole_move_list,
-                            subgoal,
-                        );
-                    }
-                }
-            }
+    for movement in phase.moves.iter() {
+        if !can_follow(last_move, *movement) {
+            continue;
+        }
+        let next = phase.do_move(coordinates, *movement);
+        let next_distance = phase.next_distance(distance, next);
+        if next_distance >= depth || phase.extra_bound(next) >= depth {
+            continue;
+        }
+        move_list.push(*movement);
+        if tree_search(
+            phase,
+            next,
+            next_distance,
+            depth - 1,
+            *movement,
+            move_list,
+            tracker,
+            visit,
+        ) {
+            return true;
         }
+        move_list.pop();
     }
+    false
 }
 
-/// Pattern matching function that inputs a `Cube` and returns a `Cube` that has
-/// had the required move applied to it.
+/// Function that inputs a `Cube` and returns a `Cube` that has had the
+/// required move applied to it. Every turn of a face is a single
+/// multiplication by a cube from `physical::face_turns`.
 ///
 /// # Parameters
 /// * `rubiks` - The `Cube` to apply the `movement` to.
@@ -223,142 +898,111 @@ fn tree_search(
 /// # Returns
 /// * `Cube` - A `Cube` with the `movement` applied to it.
 pub fn do_move(mut rubiks: physical::Cube, movement: Moves) -> physical::Cube {
+    if (movement as usize) < 18 {
+        return rubiks.multiply(&physical::face_turns()[movement as usize]);
+    }
     match movement {
-        Moves::F1 => rubiks.f(),
-        Moves::F2 => {
-            rubiks.f();
-            rubiks.f()
-        }
-        Moves::F3 => {
-            rubiks.f();
-            rubiks.f();
-            rubiks.f()
-        }
-        Moves::B1 => rubiks.b(),
-        Moves::B2 => {
-            rubiks.b();
-            rubiks.b()
-        }
-        Moves::B3 => {
-            rubiks.b();
-            rubiks.b();
-            rubiks.b()
-        }
-        Moves::U1 => rubiks.u(),
-        Moves::U2 => {
-            rubiks.u();
-            rubiks.u()
-        }
-        Moves::U3 => {
-            rubiks.u();
-            rubiks.u();
-            rubiks.u()
-        }
-        Moves::D1 => rubiks.d(),
-        Moves::D2 => {
-            rubiks.d();
-            rubiks.d()
-        }
-        Moves::D3 => {
-            rubiks.d();
-            rubiks.d();
-            rubiks.d()
-        }
-        Moves::L1 => rubiks.l(),
-        Moves::L2 => {
-            rubiks.l();
-            rubiks.l()
-        }
-        Moves::L3 => {
-            rubiks.l();
-            rubiks.l();
-            rubiks.l()
-        }
-        Moves::R1 => rubiks.r(),
-        Moves::R2 => {
-            rubiks.r();
-            rubiks.r()
-        }
-        Moves::R3 => {
-            rubiks.r();
-            rubiks.r();
-            rubiks.r()
-        }
         Moves::NONE => {}
+        _ => {
+            let turn: fn(&mut physical::Cube) = match movement as usize / 3 {
+                6 => physical::Cube::m,
+                7 => physical::Cube::e,
+                8 => physical::Cube::s,
+                9 => physical::Cube::x,
+                10 => physical::Cube::y,
+                11 => physical::Cube::z,
+                // A wide move is the opposite face and a rotation.
+                12 => |c| {
+                    c.b();
+                    c.z()
+                },
+                13 => |c| {
+                    c.f();
+                    c.z();
+                    c.z();
+                    c.z()
+                },
+                14 => |c| {
+                    c.d();
+                    c.y()
+                },
+                15 => |c| {
+                    c.u();
+                    c.y();
+                    c.y();
+                    c.y()
+                },
+                16 => |c| {
+                    c.r();
+                    c.x();
+                    c.x();
+                    c.x()
+                },
+                _ => |c| {
+                    c.l();
+                    c.x()
+                },
+            };
+            for _ in 0..(movement as usize % 3 + 1) {
+                turn(&mut rubiks);
+            }
+        }
     }
     rubiks
 }
 
-/// A pattern matching function which dictates what `Moves` are mathematically
-/// equal according to our group theory definitions of the cube.
+/// A pattern matching function that gives the face a `Moves` turns, with
+/// opposite faces next to each other so `face / 2` names the axis.
 ///
 /// # Parameters
-/// * `movement` - A `Moves` to find the opposite equal of.
+/// * `movement` - A `Moves` to find the face of.
 /// # Returns
-/// * `Moves` - The mathematical equal of `movement`.
-fn opposite_move(movement: Moves) -> Moves {
-    let a = match movement {
-        Moves::F1 => Moves::B1,
-        Moves::F2 => Moves::B2,
-        Moves::F3 => Moves::B3,
-        Moves::B1 => Moves::F1,
-        Moves::B2 => Moves::F2,
-        Moves::B3 => Moves::F3,
-        Moves::U1 => Moves::D1,
-        Moves::U2 => Moves::D2,
-        Moves::U3 => Moves::D3,
-        Moves::D1 => Moves::U1,
-        Moves::D2 => Moves::U2,
-        Moves::D3 => Moves::U3,
-        Moves::L1 => Moves::R1,
-        Moves::L2 => Moves::R2,
-        Moves::L3 => Moves::R3,
-        Moves::R1 => Moves::L1,
-        Moves::R2 => Moves::L2,
-        Moves::R3 => Moves::L3,
-        Moves::NONE => Moves::NONE,
-    };
-    a
+/// * `usize` - 0 to 5 for F, B, U, D, L, R; 6 for any other move.
+fn face(movement: Moves) -> usize {
+    match movement {
+        Moves::F1 | Moves::F2 | Moves::F3 => 0,
+        Moves::B1 | Moves::B2 | Moves::B3 => 1,
+        Moves::U1 | Moves::U2 | Moves::U3 => 2,
+        Moves::D1 | Moves::D2 | Moves::D3 => 3,
+        Moves::L1 | Moves::L2 | Moves::L3 => 4,
+        Moves::R1 | Moves::R2 | Moves::R3 => 5,
+        _ => 6,
+    }
 }
 
-/// A pattern matching function that dictates which `Moves` should not follow
-/// another as they essentially repetitions.
+/// The move that undoes `movement`.
+pub fn inverse_move(movement: Moves) -> Moves {
+    if movement == Moves::NONE {
+        return movement;
+    }
+    let m = movement as usize;
+    ALL_MOVES[m - m % 3 + 2 - m % 3]
+}
+
+/// Dictates which `Moves` are worth doing straight after another.
+///
+/// Two turns of the same face can always be written as one, and turns of
+/// opposite faces commute, so those are only searched in one order.
 ///
 /// # Parameters
-/// * `movement` - The `Moves` of which to find the matching `Moves`.
+/// * `last_move` - The previous `Moves` done, or `Moves::NONE`.
+/// * `movement` - The `Moves` we want to do next.
 /// # Returns
-/// * `Moves` - The `Moves` the shouldn't follow `movement`
-fn cannot_follow(movement: Moves) -> Moves {
-    let a = match movement {
-        Moves::F1 => Moves::F2,
-        Moves::F2 => Moves::F1,
-        Moves::F3 => Moves::F2,
-        Moves::B1 => Moves::B2,
-        Moves::B2 => Moves::B1,
-        Moves::B3 => Moves::B2,
-        Moves::U1 => Moves::U2,
-        Moves::U2 => Moves::U1,
-        Moves::U3 => Moves::U2,
-        Moves::D1 => Moves::D2,
-        Moves::D2 => Moves::D1,
-        Moves::D3 => Moves::D2,
-        Moves::L1 => Moves::L2,
-        Moves::L2 => Moves::L1,
-        Moves::L3 => Moves::L2,
-        Moves::R1 => Moves::R2,
-        Moves::R2 => Moves::R1,
-        Moves::R3 => Moves::R2,
-        Moves::NONE => Moves::NONE,
-    };
-    a
+/// * `bool` - True if `movement` should be tried after `last_move`.
+fn can_follow(last_move: Moves, movement: Moves) -> bool {
+    let (last, next) = (face(last_move), face(movement));
+    if last == 6 {
+        return true;
+    }
+    last != next && !(last / 2 == next / 2 && last > next)
 }
 
 ///*****************************************************************************
 ///* Constant values.
 ///****************************************************************************
 
-const MAX_PHASE_ONE_DEPTH: usize = 21;//18;
-const PHASE_ONE_MOVE_LIST: [Moves; 18] = [
+/// Every move other than `Moves::NONE`, in order.
+pub const ALL_MOVES: [Moves; 54] = [
     Moves::F1,
     Moves::F2,
     Moves::F3,
@@ -377,17 +1021,265 @@ const PHASE_ONE_MOVE_LIST: [Moves; 18] = [
     Moves::R1,
     Moves::R2,
     Moves::R3,
+    Moves::M1,
+    Moves::M2,
+    Moves::M3,
+    Moves::E1,
+    Moves::E2,
+    Moves::E3,
+    Moves::S1,
+    Moves::S2,
+    Moves::S3,
+    Moves::X1,
+    Moves::X2,
+    Moves::X3,
+    Moves::Y1,
+    Moves::Y2,
+    Moves::Y3,
+    Moves::Z1,
+    Moves::Z2,
+    Moves::Z3,
+    Moves::Fw1,
+    Moves::Fw2,
+    Moves::Fw3,
+    Moves::Bw1,
+    Moves::Bw2,
+    Moves::Bw3,
+    Moves::Uw1,
+    Moves::Uw2,
+    Moves::Uw3,
+    Moves::Dw1,
+    Moves::Dw2,
+    Moves::Dw3,
+    Moves::Lw1,
+    Moves::Lw2,
+    Moves::Lw3,
+    Moves::Rw1,
+    Moves::Rw2,
+    Moves::Rw3,
 ];
-const MAX_PHASE_TWO_DEPTH: usize = 10;//12;
-const PHASE_TWO_MOVE_LIST: [Moves; 6] = [
-    Moves::U1,
-    //Moves::U2,
-    //Moves::U3,
-    Moves::B2,
+const MAX_PHASE_ONE_DEPTH: usize = 12;
+pub const PHASE_ONE_MOVE_LIST: [Moves; 18] = [
+    Moves::F1,
     Moves::F2,
+    Moves::F3,
+    Moves::B1,
+    Moves::B2,
+    Moves::B3,
+    Moves::U1,
+    Moves::U2,
+    Moves::U3,
     Moves::D1,
-    //Moves::D2,
-    //Moves::D3,
+    Moves::D2,
+    Moves::D3,
+    Moves::L1,
     Moves::L2,
+    Moves::L3,
+    Moves::R1,
     Moves::R2,
+    Moves::R3,
 ];
+const MAX_PHASE_TWO_DEPTH: usize = 18;
+/// The generators of G1. Any more and the cube could leave the group, any
+/// less and not every G1 state can be reached.
+pub const PHASE_TWO_MOVE_LIST: [Moves; 10] = [
+    Moves::U1,
+    Moves::U2,
+    Moves::U3,
+    Moves::D1,
+    Moves::D2,
+    Moves::D3,
+    Moves::R2,
+    Moves::L2,
+    Moves::F2,
+    Moves::B2,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solver::test_util::{check, scrambled, setup, superflip};
+
+    fn solve(rubiks: &physical::Cube) -> Vec<Moves> {
+        let phase_one = Phase::one();
+        let mut solution = search(&phase_one, phase_one.coordinates(rubiks));
+        let mut c = rubiks.clone();
+        for movement in solution.iter() {
+            c = do_move(c, *movement);
+        }
+        assert_eq!(phase_one.coordinates(&c), [0, 0, 0]);
+
+        let phase_two = Phase::two();
+        solution.extend(search(&phase_two, phase_two.coordinates(&c)));
+        solution
+    }
+
+    #[test]
+    fn test_phase_distances() {
+        setup();
+        let phase_one = Phase::one();
+        let phase_two = Phase::two();
+        let solved = physical::Cube::new();
+        assert_eq!(phase_one.distance(phase_one.coordinates(&solved)), 0);
+        assert_eq!(phase_two.distance(phase_two.coordinates(&solved)), 0);
+
+        let rubiks = scrambled(&[Moves::R1]);
+        assert_eq!(phase_one.distance(phase_one.coordinates(&rubiks)), 1);
+        let rubiks = scrambled(&[Moves::R1, Moves::U1, Moves::F1]);
+        assert_eq!(phase_one.distance(phase_one.coordinates(&rubiks)), 3);
+        let rubiks = scrambled(&[Moves::R2, Moves::U1, Moves::F2, Moves::D3]);
+        assert_eq!(phase_two.distance(phase_two.coordinates(&rubiks)), 4);
+    }
+
+    #[test]
+    fn test_search_with_context() {
+        use std::sync::mpsc;
+
+        setup();
+        let rubiks = scrambled(&[
+            Moves::R1, Moves::U2, Moves::F3, Moves::L1, Moves::D2, Moves::B1, Moves::U3, Moves::R2,
+        ]);
+        let (sender, receiver) = mpsc::channel();
+        let context = SolverContext::new().with_progress(move |progress| sender.send(*progress).unwrap());
+        let solution = complete_search_with_context(&rubiks, context).unwrap().moves;
+        let reports: Vec<Progress> = receiver.iter().collect();
+        assert!(reports.iter().any(|p| p.phase == 1));
+        assert!(reports.iter().any(|p| p.phase == 2));
+        assert!(reports.windows(2).all(|w| w[0].nodes <= w[1].nodes && w[0].phase <= w[1].phase));
+        check(&rubiks, &solution);
+
+        let superflip = superflip();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let context = SolverContext::new().with_cancel_token(cancel);
+        assert_eq!(complete_search_with_context(&superflip, context), Err(SolveError::Cancelled));
+
+        let context = SolverContext::new().with_timeout(Duration::from_millis(0));
+        let phase_one = Phase::one();
+        assert_eq!(
+            search_with_context(&phase_one, phase_one.coordinates(&superflip), context),
+            Err(SolveError::TimedOut)
+        );
+    }
+
+    #[test]
+    fn test_complete_search_result() {
+        setup();
+        let scramble = [
+            Moves::F1, Moves::L2, Moves::D3, Moves::B1, Moves::R1, Moves::U2, Moves::L3, Moves::F2, Moves::U1,
+        ];
+        let rubiks = scrambled(&scramble);
+        let result = complete_search(&rubiks).unwrap();
+        assert_eq!(result.moves, simplify(&[&result.phase_one[..], &result.phase_two[..]].concat()));
+        assert!(result.nodes > 0);
+        assert!(result.quarter_turn_metric() >= result.half_turn_metric());
+        check(&rubiks, &result.moves);
+
+        let mut twisted = physical::Cube::new();
+        twisted.corners.orientation[0] = 1;
+        assert_eq!(
+            complete_search(&twisted),
+            Err(SolveError::Unsolvable(physical::Unsolvable::CornerTwist))
+        );
+        let mut swapped = physical::Cube::new();
+        swapped.edges.permutation.swap(0, 1);
+        assert_eq!(complete_search(&swapped), Err(SolveError::Unsolvable(physical::Unsolvable::Parity)));
+    }
+
+    #[test]
+    fn test_budgeted_search_improves() {
+        use std::sync::mpsc;
+
+        setup();
+        let scramble = [
+            Moves::R1, Moves::U2, Moves::F3, Moves::L1, Moves::D2, Moves::B1, Moves::U3,
+            Moves::R2, Moves::F1, Moves::D1, Moves::L3, Moves::B2, Moves::U1, Moves::R3,
+        ];
+        let rubiks = scrambled(&scramble);
+        let limit = 500_000;
+        let (sender, receiver) = mpsc::channel();
+        let context = SolverContext::new()
+            .with_node_limit(limit)
+            .with_improvement(move |s| sender.send(s.len()).unwrap());
+        // How far the search gets depends on the move order and the tables,
+        // so only hold it to stopping in time with a solution, if it has one.
+        match complete_search_with_budget(&rubiks, context) {
+            Ok(result) => {
+                // The positions are counted a batch at a time.
+                assert!(result.nodes <= limit + TRACKER_BATCH);
+                let lengths: Vec<usize> = receiver.iter().collect();
+                assert!(lengths.windows(2).all(|w| w[1] < w[0]));
+                assert_eq!(*lengths.last().unwrap(), result.phase_one.len() + result.phase_two.len());
+                assert_eq!(result.moves, simplify(&[&result.phase_one[..], &result.phase_two[..]].concat()));
+                check(&rubiks, &result.moves);
+            }
+            Err(e) => assert_eq!(e, SolveError::NodeLimit),
+        }
+
+        // A target length stops the search at the first solution that short.
+        let (sender, receiver) = mpsc::channel();
+        let context = SolverContext::new()
+            .with_target_length(MAX_PHASE_ONE_DEPTH + MAX_PHASE_TWO_DEPTH)
+            .with_improvement(move |_| sender.send(()).unwrap());
+        assert!(complete_search_with_budget(&rubiks, context).is_ok());
+        assert_eq!(receiver.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_budgeted_search_errors() {
+        setup();
+        let mut twisted = physical::Cube::new();
+        twisted.corners.orientation[0] = 1;
+        assert_eq!(
+            complete_search_with_budget(&twisted, SolverContext::new()),
+            Err(SolveError::Unsolvable(physical::Unsolvable::CornerTwist))
+        );
+        let mut duplicated = physical::Cube::new();
+        duplicated.edges.permutation[0] = duplicated.edges.permutation[1];
+        assert_eq!(
+            complete_search_with_budget(&duplicated, SolverContext::new()),
+            Err(SolveError::Unsolvable(physical::Unsolvable::MissingCubies))
+        );
+
+        // Running out of budget before any solution is not the same as
+        // having nothing to do.
+        let solved = complete_search_with_budget(&physical::Cube::new(), SolverContext::new()).unwrap();
+        assert!(solved.moves.is_empty());
+        let superflip = superflip();
+        let context = SolverContext::new().with_timeout(Duration::from_millis(0));
+        assert_eq!(
+            complete_search_with_budget(&superflip, context),
+            Err(SolveError::TimedOut)
+        );
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert_eq!(
+            complete_search_with_budget(&superflip, SolverContext::new().with_cancel_token(cancel)),
+            Err(SolveError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_two_phase_solves_scramble() {
+        setup();
+        let rubiks = scrambled(&[Moves::R1, Moves::U1, Moves::F3, Moves::L2, Moves::D1]);
+        check(&rubiks, &solve(&rubiks));
+    }
+
+    #[test]
+    fn test_two_phase_solves_long_scrambles() {
+        setup();
+        let mut seed: u64 = 12345;
+        for _ in 0..20 {
+            let mut scramble = Vec::new();
+            for _ in 0..40 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                scramble.push(PHASE_ONE_MOVE_LIST[(seed >> 33) as usize % 18]);
+            }
+            let rubiks = scrambled(&scramble);
+            let solution = solve(&rubiks);
+            assert!(solution.len() <= MAX_PHASE_ONE_DEPTH + MAX_PHASE_TWO_DEPTH);
+            check(&rubiks, &solution);
+        }
+    }
+}
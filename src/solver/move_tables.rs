@@ -0,0 +1,178 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Precomputed move tables for the coordinates used by the two-phase search.
+//!
+//! A move table maps a coordinate and a move to the coordinate reached by
+//! doing that move. With them the search only deals in plain integers and
+//! never has to turn a whole `Cube`. Further explanation at
+//! (http://kociemba.org/math/movetables.htm)
+
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use physical;
+use solver::table_file::{self, Storage, TableKind};
+use solver::{do_move, Moves, PHASE_ONE_MOVE_LIST, PHASE_TWO_MOVE_LIST};
+
+/// The number of columns in every table. Columns follow the order of the
+/// `Moves` enum, so `movement as usize` picks the right one.
+pub const N_MOVE: usize = 18;
+
+pub const N_TWIST: usize = 2187;
+pub const N_FLIP: usize = 2048;
+pub const N_SLICE: usize = 495;
+pub const N_CORNER_PERMUTATION: usize = 40320;
+pub const N_EDGE_PERMUTATION: usize = 40320;
+pub const N_SORTED_SLICE: usize = 11880;
+
+/// A table of where each move takes a single coordinate.
+///
+/// # Variables
+/// * `size` - The number of values the coordinate can take.
+/// * `table` - `size * N_MOVE` entries, the new coordinate for each
+///     coordinate and move. Moves the table was not built for are left as
+///     `u16::MAX`.
+pub struct MoveTable {
+    size: usize,
+    table: Storage<u16>,
+}
+
+impl MoveTable {
+    /// Loads a move table from the table cache, generating it if needed.
+    ///
+    /// # Parameters
+    /// * `kind` - Which table this is in the cache.
+    /// * `size` - The number of values the coordinate can take.
+    /// * `moves` - The `Moves` to build the table for.
+    /// * `coordinate` - Reads the coordinate off a `Cube`.
+    /// # Return
+    /// * `MoveTable`
+    pub fn new(
+        kind: TableKind,
+        size: usize,
+        moves: &[Moves],
+        coordinate: fn(&physical::Cube) -> usize,
+    ) -> MoveTable {
+        MoveTable {
+            size,
+            table: table_file::load_or_build(kind, size, N_MOVE, || {
+                MoveTable::generate(size, moves, coordinate)
+            }),
+        }
+    }
+
+    /// Builds a move table with a breadth first search from the solved cube.
+    ///
+    /// Every coordinate reached keeps one `Cube` as an example, which is
+    /// enough as the result of a move only depends on the coordinate.
+    fn generate(size: usize, moves: &[Moves], coordinate: fn(&physical::Cube) -> usize) -> Vec<u16> {
+        let mut table = vec![u16::MAX; size * N_MOVE];
+        let mut seen = vec![false; size];
+        let mut queue = VecDeque::new();
+
+        let solved = physical::Cube::new();
+        seen[coordinate(&solved)] = true;
+        queue.push_back(solved);
+
+        while let Some(rubiks) = queue.pop_front() {
+            let from = coordinate(&rubiks);
+            for movement in moves.iter() {
+                let next = do_move(rubiks, *movement);
+                let to = coordinate(&next);
+                table[from * N_MOVE + *movement as usize] = to as u16;
+                if !seen[to] {
+                    seen[to] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        table
+    }
+
+    /// The number of values the coordinate can take.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Looks up the coordinate reached by doing `movement`.
+    ///
+    /// # Parameters
+    /// * `coordinate` - The coordinate before the move.
+    /// * `movement` - The `Moves` to do.
+    /// # Return
+    /// * `usize` - The coordinate after the move.
+    #[inline]
+    pub fn do_move(&self, coordinate: usize, movement: Moves) -> usize {
+        self.table[coordinate * N_MOVE + movement as usize] as usize
+    }
+}
+
+/// Every move table the two-phase search needs.
+///
+/// The phase one coordinates and the two permutation coordinates that are
+/// defined everywhere cover all 18 moves. `edge_permutation` only makes
+/// sense inside G1, so it only covers the phase two moves.
+pub struct MoveTables {
+    pub twist: MoveTable,
+    pub flip: MoveTable,
+    pub slice: MoveTable,
+    pub corner_permutation: MoveTable,
+    pub edge_permutation: MoveTable,
+    pub sorted_slice: MoveTable,
+}
+
+impl MoveTables {
+    /// Generates all the move tables.
+    ///
+    /// # Return
+    /// * `MoveTables`
+    pub fn new() -> MoveTables {
+        MoveTables {
+            twist: MoveTable::new(TableKind::TwistMove, N_TWIST, &PHASE_ONE_MOVE_LIST, |c| {
+                c.corner_orientation() as usize
+            }),
+            flip: MoveTable::new(TableKind::FlipMove, N_FLIP, &PHASE_ONE_MOVE_LIST, |c| {
+                c.edge_orientation() as usize
+            }),
+            slice: MoveTable::new(TableKind::SliceMove, N_SLICE, &PHASE_ONE_MOVE_LIST, |c| {
+                c.ud_slice() as usize
+            }),
+            corner_permutation: MoveTable::new(
+                TableKind::CornerPermutationMove,
+                N_CORNER_PERMUTATION,
+                &PHASE_ONE_MOVE_LIST,
+                |c| c.corner_permutation() as usize,
+            ),
+            edge_permutation: MoveTable::new(
+                TableKind::EdgePermutationMove,
+                N_EDGE_PERMUTATION,
+                &PHASE_TWO_MOVE_LIST,
+                |c| c.phase_two_edge_permutation() as usize,
+            ),
+            sorted_slice: MoveTable::new(
+                TableKind::SortedSliceMove,
+                N_SORTED_SLICE,
+                &PHASE_ONE_MOVE_LIST,
+                |c| c.ud_sorted_slice() as usize,
+            ),
+        }
+    }
+}
+
+/// The move tables shared by every search, generated on first use.
+///
+/// # Return
+/// * `&'static MoveTables`
+pub fn tables() -> &'static MoveTables {
+    static TABLES: OnceLock<MoveTables> = OnceLock::new();
+    TABLES.get_or_init(MoveTables::new)
+}
@@ -0,0 +1,296 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Reading and writing moves in Singmaster notation, as in `R U R' U2 F2`.
+//!
+//! A move is a face letter, then optionally how many quarter turns (1, 2 or
+//! 3) and a prime for anti-clockwise. `R2'` and `R3` are both accepted, and
+//! the spaces between moves may be left out. Slice moves are `M`, `E` and
+//! `S`, rotations are `x`, `y` and `z`, and wide moves are written either
+//! `Rw` or `r`.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use solver::{Moves, ALL_MOVES};
+
+/// How each layer of `Moves` is written.
+const LAYERS: [&str; 18] = [
+    "F", "B", "U", "D", "L", "R", "M", "E", "S", "x", "y", "z", "Fw", "Bw", "Uw", "Dw", "Lw", "Rw",
+];
+
+/// The first layer of the wide moves.
+const WIDE: usize = 12;
+
+/// Why some notation could not be read.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Something that does not start with a face letter.
+    UnknownMove,
+    /// A number of quarter turns other than 1, 2 or 3.
+    BadAmount,
+    /// Nothing to read, where one move was wanted.
+    Empty,
+    /// A bracket or separator where it does not belong.
+    Unexpected,
+    /// A bracket that is never closed.
+    Unclosed,
+    /// An algorithm that would expand to more than `MAX_EXPANSION` moves.
+    TooLong,
+}
+
+/// An error reading notation, and where it is.
+///
+/// # Variables
+/// * `kind` - What went wrong.
+/// * `span` - The bytes of the input holding the bad move.
+/// * `token` - The bad move as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>,
+    pub token: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, input: &str, span: Range<usize>) -> ParseError {
+        ParseError {
+            kind,
+            token: input[span.clone()].to_string(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ParseErrorKind::UnknownMove => "unknown move",
+            ParseErrorKind::BadAmount => "turns must be 1, 2 or 3 in",
+            ParseErrorKind::Empty => return write!(f, "no move given"),
+            ParseErrorKind::Unexpected => "unexpected",
+            ParseErrorKind::Unclosed => "unclosed",
+            ParseErrorKind::TooLong => "too many moves in",
+        };
+        write!(f, "{} `{}` at {}..{}", what, self.token, self.span.start, self.span.end)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Reads a sequence of moves, such as `R U R' U2 F2`.
+///
+/// # Parameters
+/// * `input` - The moves, with or without whitespace between them.
+/// # Returns
+/// * `Result<Vec<Moves>, ParseError>` - The moves, or the first one that
+///     could not be read.
+pub fn parse_moves(input: &str) -> Result<Vec<Moves>, ParseError> {
+    let mut moves = Vec::new();
+    let mut start = 0;
+    while let Some(c) = input[start..].chars().next() {
+        if c.is_whitespace() {
+            start = start + c.len_utf8();
+            continue;
+        }
+        match read_move(input, start)? {
+            Some((movement, end)) => {
+                moves.push(movement);
+                start = end;
+            }
+            None => return Err(unknown_move(input, start, |c| c.is_whitespace())),
+        }
+    }
+    Ok(moves)
+}
+
+/// Reads the move starting at byte `start` of `input`.
+///
+/// # Returns
+/// * `Result<Option<(Moves, usize)>, ParseError>` - The move and the byte
+///     just after it, None if there is no face letter at `start`, or an
+///     error if the turns are wrong.
+pub(crate) fn read_move(input: &str, start: usize) -> Result<Option<(Moves, usize)>, ParseError> {
+    let bytes = input.as_bytes();
+    if !bytes[start].is_ascii() {
+        return Ok(None);
+    }
+    let letter = bytes[start] as char;
+    let mut end = start + 1;
+    let layer = if let Some(face) = "FBUDLR".find(letter) {
+        if end < bytes.len() && bytes[end] == b'w' {
+            end = end + 1;
+            face + WIDE
+        } else {
+            face
+        }
+    } else if let Some(face) = "fbudlr".find(letter) {
+        face + WIDE
+    } else if let Some(layer) = "MESxyz".find(letter) {
+        layer + 6
+    } else {
+        return Ok(None);
+    };
+
+    let mut turns = 1;
+    if end < bytes.len() && bytes[end].is_ascii_digit() {
+        end = end + 1;
+        turns = (bytes[end - 1] - b'0') as usize;
+        if !(1..=3).contains(&turns) {
+            return Err(ParseError::new(ParseErrorKind::BadAmount, input, start..end));
+        }
+    }
+    for prime in ["'", "\u{2019}"].iter() {
+        if input[end..].starts_with(prime) {
+            end = end + prime.len();
+            turns = 4 - turns;
+            break;
+        }
+    }
+    Ok(Some((ALL_MOVES[layer * 3 + turns - 1], end)))
+}
+
+/// The error for something at byte `start` that is not a move. The whole
+/// word is blamed, up to the first character `ends` accepts, so the error
+/// shows what was meant.
+pub(crate) fn unknown_move<F>(input: &str, start: usize, ends: F) -> ParseError
+where
+    F: Fn(char) -> bool,
+{
+    let mut end = start;
+    for c in input[start..].chars() {
+        if end > start && ends(c) {
+            break;
+        }
+        end = end + c.len_utf8();
+    }
+    ParseError::new(ParseErrorKind::UnknownMove, input, start..end)
+}
+
+impl FromStr for Moves {
+    type Err = ParseError;
+
+    /// Reads a single move, such as `U2` or `R'`.
+    fn from_str(s: &str) -> Result<Moves, ParseError> {
+        let moves = parse_moves(s)?;
+        match moves.len() {
+            0 => Err(ParseError::new(ParseErrorKind::Empty, s, 0..s.len())),
+            1 => Ok(moves[0]),
+            _ => Err(ParseError::new(ParseErrorKind::UnknownMove, s, 0..s.len())),
+        }
+    }
+}
+
+impl fmt::Display for Moves {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == Moves::NONE {
+            return Ok(());
+        }
+        let m = *self as usize;
+        let suffix = ["", "2", "'"][m % 3];
+        write!(f, "{}{}", LAYERS[m / 3], suffix)
+    }
+}
+
+/// Writes a move sequence in Singmaster notation.
+///
+/// # Variables
+/// * `moves` - The moves to write.
+/// * `separator` - What goes between two moves. A space by default.
+#[derive(Debug, Copy, Clone)]
+pub struct Notation<'a> {
+    moves: &'a [Moves],
+    separator: &'a str,
+}
+
+impl<'a> Notation<'a> {
+    pub fn new(moves: &'a [Moves]) -> Notation<'a> {
+        Notation { moves, separator: " " }
+    }
+
+    pub fn separator(mut self, separator: &'a str) -> Notation<'a> {
+        self.separator = separator;
+        self
+    }
+}
+
+impl<'a> fmt::Display for Notation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for movement in self.moves.iter().filter(|m| **m != Moves::NONE) {
+            if !first {
+                write!(f, "{}", self.separator)?;
+            }
+            write!(f, "{}", movement)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `moves` in Singmaster notation, separated by spaces.
+pub fn format_moves(moves: &[Moves]) -> String {
+    Notation::new(moves).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(
+            parse_moves("R U R' U2 F2").unwrap(),
+            vec![Moves::R1, Moves::U1, Moves::R3, Moves::U2, Moves::F2]
+        );
+        assert_eq!(
+            parse_moves(" RUR'U'\tB3 D2' L1 ").unwrap(),
+            vec![Moves::R1, Moves::U1, Moves::R3, Moves::U3, Moves::B3, Moves::D2, Moves::L1]
+        );
+        assert_eq!(parse_moves("F\u{2019}").unwrap(), vec![Moves::F3]);
+        assert!(parse_moves("").unwrap().is_empty());
+        assert_eq!("U'".parse::<Moves>(), Ok(Moves::U3));
+        assert_eq!(
+            parse_moves("M2 E' S x y2 z' Rw r' Uw2 f").unwrap(),
+            vec![
+                Moves::M2, Moves::E3, Moves::S1, Moves::X1, Moves::Y2, Moves::Z3, Moves::Rw1, Moves::Rw3,
+                Moves::Uw2, Moves::Fw1,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_moves("R U X' F").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::UnknownMove);
+        assert_eq!(e.span, 4..6);
+        assert_eq!(e.token, "X'");
+
+        let e = parse_moves("R U5").unwrap_err();
+        assert_eq!(e.kind, ParseErrorKind::BadAmount);
+        assert_eq!(e.span, 2..4);
+
+        assert_eq!("".parse::<Moves>().unwrap_err().kind, ParseErrorKind::Empty);
+        assert!("R U".parse::<Moves>().is_err());
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let text = "F B2 U' D L2 R'";
+        let moves = parse_moves(text).unwrap();
+        assert_eq!(format_moves(&moves), text);
+        assert_eq!(Notation::new(&moves).separator(",").to_string(), "F,B2,U',D,L2,R'");
+        for movement in ALL_MOVES.iter() {
+            assert_eq!(movement.to_string().parse::<Moves>(), Ok(*movement));
+        }
+    }
+}
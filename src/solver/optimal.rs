@@ -0,0 +1,682 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Optimal solving in the half-turn metric.
+//!
+//! This is one IDA* search on the whole cube, as in Korf's "Finding Optimal
+//! Solutions to Rubik's Cube Using Pattern Databases". The heuristic is the
+//! largest of several distances, each of which only looks at part of the
+//! cube: the corners on their own, and the distance to G1 seen from each of
+//! the three axes. Deep searches take the distance to G1 with the UD slice
+//! edges home instead, from Kociemba's "huge" phase one table, and near the
+//! leaves look up the distance to G1 of the cube's inverse too, as a
+//! solution of the inverse is a solution undone backwards. Symmetries of the
+//! cube and of the search itself keep it from searching the same sequences
+//! twice. The search is finished once a depth
+//! has been searched completely, so the first solution found is proven to
+//! be the shortest.
+
+use std::sync::OnceLock;
+
+use physical::{self, symmetry};
+use solver::move_tables::{self, MoveTable, MoveTables};
+use solver::pruning_tables::{self, SymPruningTable};
+use solver::symmetry_tables::{self, SymCoordinate};
+use solver::table_file::TableKind;
+use solver::{
+    can_follow, do_move, simplify, Moves, Phase, SolveError, SolveResult, SolverContext, Tracker, MAX_PHASE_ONE_DEPTH,
+    PHASE_ONE_MOVE_LIST,
+};
+
+/// Every cube can be solved in 20 moves, so the search never goes further.
+pub const MAX_OPTIMAL_DEPTH: usize = 20;
+
+/// Searches shorter than this are quick on the phase one tables alone, so
+/// they do without the `HugeTables`, which take minutes to build.
+const HUGE_TABLE_DEPTH: usize = 17;
+
+/// The tables only the optimal search needs, which are small enough to build
+/// for any search.
+///
+/// # Variables
+/// * `corner_moves` - The `MoveTable` of the corner permutation.
+/// * `corners` - The distance of the corners on their own, over the corner
+///     permutation classes and the corner twist.
+/// * `axis_moves` - For each of `symmetry::AXES` and each move `m`, the move
+///     `S^-1 * m * S`.
+/// * `conjugate_moves` - The same for every symmetry.
+struct OptimalTables {
+    corner_moves: &'static MoveTable,
+    corners: SymPruningTable,
+    axis_moves: [[Moves; 18]; 3],
+    conjugate_moves: [[Moves; 18]; symmetry::N_SYM],
+}
+
+impl OptimalTables {
+    fn new() -> OptimalTables {
+        let tables = move_tables::tables();
+        let symmetry_tables = symmetry_tables::tables();
+        let corners = SymPruningTable::new(
+            TableKind::CornerTwistPruning,
+            &symmetry_tables.corner_permutation,
+            |corners, movement| {
+                move_tables::tables().corner_permutation.do_move(corners, movement)
+            },
+            &symmetry_tables.twist,
+            &tables.twist,
+            &PHASE_ONE_MOVE_LIST,
+            2,
+        );
+
+        let mut axis_moves = [[Moves::NONE; 18]; 3];
+        for a in 0..3 {
+            for m in 0..18 {
+                axis_moves[a][m] = symmetry_tables.conjugate_move(PHASE_ONE_MOVE_LIST[m], symmetry::AXES[a]);
+            }
+        }
+
+        let mut conjugate_moves = [[Moves::NONE; 18]; symmetry::N_SYM];
+        for s in 0..symmetry::N_SYM {
+            for m in 0..18 {
+                conjugate_moves[s][m] = symmetry_tables.conjugate_move(PHASE_ONE_MOVE_LIST[m], s);
+            }
+        }
+
+        OptimalTables {
+            corner_moves: &tables.corner_permutation,
+            corners,
+            axis_moves,
+            conjugate_moves,
+        }
+    }
+}
+
+/// The tables that make long optimal searches fast. Together they take about
+/// 1GB, and minutes to build.
+///
+/// # Variables
+/// * `moves` - The `MoveTables` of the coordinates the search follows.
+/// * `flip_sorted_slice_twist` - The distance to the cubes in G1 that also
+///     have their UD slice edges home, over the flip and sorted UD slice
+///     classes and the corner twist. This is Kociemba's "huge" phase one
+///     table.
+/// * `flip_slice_twist` - The exact distance to G1, over the flip and UD
+///     slice classes and the corner twist, for cubes that are not a move
+///     away from one we know the distance of.
+struct HugeTables {
+    moves: &'static MoveTables,
+    flip_sorted_slice_twist: SymPruningTable,
+    flip_slice_twist: SymPruningTable,
+}
+
+impl HugeTables {
+    fn new() -> HugeTables {
+        let tables = move_tables::tables();
+        let symmetry_tables = symmetry_tables::tables();
+        let flip_sorted_slice_twist = SymPruningTable::new(
+            TableKind::FlipSortedSliceTwistPruning,
+            flip_sorted_slice(),
+            |flip_sorted_slice, movement| {
+                let tables = move_tables::tables();
+                let sorted_slice = tables.sorted_slice.do_move(flip_sorted_slice / move_tables::N_FLIP, movement);
+                let flip = tables.flip.do_move(flip_sorted_slice % move_tables::N_FLIP, movement);
+                sorted_slice * move_tables::N_FLIP + flip
+            },
+            &symmetry_tables.twist,
+            &tables.twist,
+            &PHASE_ONE_MOVE_LIST,
+            2,
+        );
+        let flip_slice_twist = SymPruningTable::new(
+            TableKind::FlipSliceTwistDistance,
+            &symmetry_tables.flip_slice,
+            pruning_tables::flip_slice_move,
+            &symmetry_tables.twist,
+            &tables.twist,
+            &PHASE_ONE_MOVE_LIST,
+            4,
+        );
+
+        HugeTables {
+            moves: tables,
+            flip_sorted_slice_twist,
+            flip_slice_twist,
+        }
+    }
+}
+
+/// Where the distance of each axis comes from.
+///
+/// * `PhaseOne` - The distance to G1, from the tables of phase one. The
+///     coordinates are the twist, flip and UD slice.
+/// * `Huge` - The distance to G1 with the UD slice edges home, from the
+///     `HugeTables`. The coordinates are the twist, flip and sorted UD
+///     slice, and the inverse of the cube is bounded too.
+enum AxisBound {
+    PhaseOne(Phase),
+    Huge(&'static HugeTables),
+}
+
+impl AxisBound {
+    /// The coordinates of a cube this bound follows.
+    fn coordinates(&self, rubiks: &physical::Cube) -> [usize; 3] {
+        match *self {
+            AxisBound::PhaseOne(ref phase) => phase.coordinates(rubiks),
+            AxisBound::Huge(_) => [
+                rubiks.corner_orientation() as usize,
+                rubiks.edge_orientation() as usize,
+                rubiks.ud_sorted_slice() as usize,
+            ],
+        }
+    }
+
+    /// The coordinates after a move.
+    #[inline]
+    fn do_move(&self, coordinates: [usize; 3], movement: Moves) -> [usize; 3] {
+        match *self {
+            AxisBound::PhaseOne(ref phase) => phase.do_move(coordinates, movement),
+            AxisBound::Huge(huge) => {
+                let [twist, flip, sorted_slice] = coordinates;
+                [
+                    huge.moves.twist.do_move(twist, movement),
+                    huge.moves.flip.do_move(flip, movement),
+                    huge.moves.sorted_slice.do_move(sorted_slice, movement),
+                ]
+            }
+        }
+    }
+
+    /// The exact distance of some coordinates.
+    fn distance(&self, coordinates: [usize; 3]) -> usize {
+        match *self {
+            AxisBound::PhaseOne(ref phase) => phase.distance(coordinates),
+            AxisBound::Huge(huge) => {
+                let [twist, flip, sorted_slice] = coordinates;
+                huge.flip_sorted_slice_twist
+                    .distance(sorted_slice * move_tables::N_FLIP + flip, twist)
+            }
+        }
+    }
+
+    /// The distance after a move, given the distance before it.
+    #[inline]
+    fn next_distance(&self, distance: usize, coordinates: [usize; 3]) -> usize {
+        match *self {
+            AxisBound::PhaseOne(ref phase) => phase.next_distance(distance, coordinates),
+            AxisBound::Huge(huge) => {
+                let [twist, flip, sorted_slice] = coordinates;
+                huge.flip_sorted_slice_twist
+                    .next_distance(distance, sorted_slice * move_tables::N_FLIP + flip, twist)
+            }
+        }
+    }
+}
+
+/// The flip and sorted UD slice together, as `sorted_slice * N_FLIP + flip`,
+/// reduced by the UD symmetries. Only the optimal search needs it, and it is
+/// big, so it is kept apart from the other `SymmetryTables`.
+fn flip_sorted_slice() -> &'static SymCoordinate {
+    static FLIP_SORTED_SLICE: OnceLock<SymCoordinate> = OnceLock::new();
+    FLIP_SORTED_SLICE.get_or_init(|| {
+        SymCoordinate::new(
+            [
+                TableKind::FlipSortedSliceClass,
+                TableKind::FlipSortedSliceRepresentative,
+                TableKind::FlipSortedSliceSelfSymmetry,
+            ],
+            symmetry_tables::N_FLIP_SORTED_SLICE,
+            symmetry_tables::N_FLIP_SORTED_SLICE_CLASS,
+            &|raw, s| {
+                let mut c = physical::Cube::new();
+                c.set_ud_sorted_slice((raw / move_tables::N_FLIP) as i32);
+                c.set_edge_orientation((raw % move_tables::N_FLIP) as i32);
+                c.edges = symmetry::conjugate_edges(&c.edges, s);
+                c.ud_sorted_slice() as usize * move_tables::N_FLIP + c.edge_orientation() as usize
+            },
+        )
+    })
+}
+
+fn tables() -> &'static OptimalTables {
+    static TABLES: OnceLock<OptimalTables> = OnceLock::new();
+    TABLES.get_or_init(OptimalTables::new)
+}
+
+fn huge_tables() -> &'static HugeTables {
+    static TABLES: OnceLock<HugeTables> = OnceLock::new();
+    TABLES.get_or_init(HugeTables::new)
+}
+
+/// A position in the optimal search.
+///
+/// # Variables
+/// * `corners` - The corner permutation.
+/// * `axes` - The coordinates of the `AxisBound` seen from each axis. The
+///     first axis is the cube as it is, so its twist is the cube's twist.
+/// * `distances` - The exact distance of the corners, then the distance of
+///     each axis.
+/// * `cube` - The whole `Cube`, for the bound from its inverse.
+#[derive(Copy, Clone)]
+struct Node {
+    corners: usize,
+    axes: [[usize; 3]; 3],
+    distances: [usize; 4],
+    cube: physical::Cube,
+}
+
+impl Node {
+    fn new(tables: &OptimalTables, bound: &AxisBound, rubiks: &physical::Cube) -> Node {
+        let mut node = Node {
+            corners: rubiks.corner_permutation() as usize,
+            axes: [[0; 3]; 3],
+            distances: [0; 4],
+            cube: *rubiks,
+        };
+        for a in 0..3 {
+            let c = symmetry::conjugate(rubiks, symmetry::AXES[a]);
+            node.axes[a] = bound.coordinates(&c);
+            node.distances[a + 1] = bound.distance(node.axes[a]);
+        }
+        node.distances[0] = tables.corners.distance(node.corners, node.axes[0][0]);
+        node
+    }
+
+    /// A lower bound on the moves left.
+    ///
+    /// This is the largest distance, plus one if the distances from all three
+    /// axes are the same and not 0. A cube one move from solved is already
+    /// in G1, with its UD slice edges home, seen from the axis of that move,
+    /// so the last move can never take all three from 1 to 0 together.
+    fn lower_bound(&self) -> usize {
+        let axes = self.distances[1];
+        let mut bound = *self.distances.iter().max().unwrap();
+        if axes != 0 && axes == self.distances[2] && axes == self.distances[3] && bound == axes {
+            bound = bound + 1;
+        }
+        bound
+    }
+
+    /// The position after `movement`, unless it needs at least `depth` more
+    /// moves to solve. The corners are checked first as their table is the
+    /// smallest, so the cheapest to look up.
+    #[inline]
+    fn child(&self, tables: &OptimalTables, bound: &AxisBound, movement: Moves, depth: usize) -> Option<Node> {
+        let mut next = *self;
+        next.axes[0] = bound.do_move(self.axes[0], movement);
+        next.corners = tables.corner_moves.do_move(self.corners, movement);
+        next.distances[0] = tables
+            .corners
+            .next_distance(self.distances[0], next.corners, next.axes[0][0]);
+        if next.distances[0] >= depth {
+            return None;
+        }
+        for a in 0..3 {
+            if a > 0 {
+                let m = tables.axis_moves[a][movement as usize];
+                next.axes[a] = bound.do_move(self.axes[a], m);
+            }
+            next.distances[a + 1] = bound.next_distance(self.distances[a + 1], next.axes[a]);
+            if next.distances[a + 1] >= depth {
+                return None;
+            }
+        }
+        if next.lower_bound() >= depth {
+            return None;
+        }
+        next.cube = do_move(self.cube, movement);
+        if let AxisBound::Huge(huge) = *bound {
+            if depth <= MAX_PHASE_ONE_DEPTH + 1 && next.inverse_needs(huge, depth) {
+                return None;
+            }
+        }
+        Some(next)
+    }
+
+    /// Whether the inverse of the cube needs at least `depth` moves to solve.
+    ///
+    /// Undoing a solution backwards solves the inverse, so the inverse needs
+    /// as many moves as the cube itself. A move does not take the inverse a
+    /// move further in the tables, so its distances to G1 are looked up
+    /// whole each time. That is only tried once the other bounds pass, and
+    /// only at depths phase one distances can reach.
+    fn inverse_needs(&self, tables: &HugeTables, depth: usize) -> bool {
+        let inverse = self.cube.inverse();
+        let mut distances = [0; 3];
+        for a in 0..3 {
+            let c = symmetry::conjugate(&inverse, symmetry::AXES[a]);
+            let flip_slice = c.ud_slice() as usize * move_tables::N_FLIP + c.edge_orientation() as usize;
+            distances[a] = tables.flip_slice_twist.distance_mod(flip_slice, c.corner_orientation() as usize);
+            if distances[a] >= depth {
+                return true;
+            }
+        }
+        distances[0] != 0 && distances[0] == distances[1] && distances[0] == distances[2] && distances[0] + 1 >= depth
+    }
+}
+
+/// Finds a shortest solution of a cube in the half-turn metric.
+///
+/// Cubes that look the same from several sides, like the superflip, only
+/// have one sequence searched from each group of sequences that those
+/// symmetries turn into each other. Searches of `HUGE_TABLE_DEPTH` moves or
+/// more first build about 1GB of tables, which takes minutes unless they
+/// are cached.
+///
+/// # Parameters
+/// * `rubiks` - The `Cube` to solve.
+/// * `context` - When to stop, and where to report progress. The search
+///     reports itself as phase 0.
+/// # Returns
+/// * `Result<SolveResult, SolveError>` - A solution no other solution is
+///     shorter than, all in `phase_one`. Otherwise why there is none: the
+///     cube cannot be solved, or the context stopped the search first.
+pub fn optimal_search(rubiks: &physical::Cube, context: SolverContext) -> Result<SolveResult, SolveError> {
+    rubiks.check_solvable()?;
+    let tables = tables();
+    let phase_one = AxisBound::PhaseOne(Phase::one());
+    let start = Node::new(tables, &phase_one, rubiks).lower_bound();
+
+    let reductions = Reductions::new(rubiks);
+
+    let mut tracker = Tracker::from_context(context);
+    let mut move_list = Vec::new();
+    for depth in start..(MAX_OPTIMAL_DEPTH + 1) {
+        if tracker.start_depth(0, depth) {
+            break;
+        }
+        if depth == 0 {
+            if is_solved(rubiks, &move_list) {
+                return Ok(solution(move_list, &tracker));
+            }
+            continue;
+        }
+        let bound = if depth < HUGE_TABLE_DEPTH {
+            AxisBound::PhaseOne(Phase::one())
+        } else {
+            AxisBound::Huge(huge_tables())
+        };
+        let root = Node::new(tables, &bound, rubiks);
+        if root.lower_bound() > depth {
+            continue;
+        }
+        if tree_search(tables, &bound, &root, depth, Moves::NONE, &reductions, &mut move_list, &mut tracker) {
+            if tracker.exhausted {
+                break;
+            }
+            return Ok(solution(move_list, &tracker));
+        }
+    }
+    Err(tracker.stopped.unwrap_or(SolveError::DepthExhausted {
+        phase: 0,
+        max_depth: MAX_OPTIMAL_DEPTH,
+    }))
+}
+
+/// Wraps an optimal solution up as a `SolveResult`.
+fn solution(moves: Vec<Moves>, tracker: &Tracker) -> SolveResult {
+    SolveResult {
+        moves: simplify(&moves),
+        phase_one: moves,
+        phase_two: Vec::new(),
+        nodes: tracker.nodes,
+        elapsed: tracker.start.elapsed(),
+    }
+}
+
+/// The tree search of the optimal solver.
+///
+/// # Parameters
+/// * `tables` - The `OptimalTables`.
+/// * `bound` - Where the distance of each axis comes from.
+/// * `node` - The position at this point in the tree.
+/// * `depth` - How many more moves we may do.
+/// * `last_move` - The move that got us here.
+/// * `reductions` - Which other orders of the moves so far are searched
+///     instead.
+/// * `move_list` - The current moves done going down the tree. When a
+///     solution is found this is left holding it.
+/// * `tracker` - Counts the positions visited, and stops the search when
+///     the context asks.
+/// # Returns
+/// * `bool` - True if a solution has been found or the search was stopped.
+#[allow(clippy::too_many_arguments)]
+fn tree_search(
+    tables: &OptimalTables,
+    bound: &AxisBound,
+    node: &Node,
+    depth: usize,
+    last_move: Moves,
+    reductions: &Reductions,
+    move_list: &mut Vec<Moves>,
+    tracker: &mut Tracker,
+) -> bool {
+    if tracker.visit() {
+        return true;
+    }
+    if depth == 0 {
+        // The distances can all be 0 with edges still swapped inside their
+        // slices, so check the actual cube.
+        return node.cube == physical::Cube::new();
+    }
+
+    for movement in PHASE_ONE_MOVE_LIST.iter() {
+        if !can_follow(last_move, *movement) {
+            continue;
+        }
+        let next_reductions = match reductions.after(tables, move_list, *movement) {
+            Some(next_reductions) => next_reductions,
+            None => continue,
+        };
+        if let Some(next) = node.child(tables, bound, *movement, depth) {
+            move_list.push(*movement);
+            if tree_search(tables, bound, &next, depth - 1, *movement, &next_reductions, move_list, tracker) {
+                return true;
+            }
+            move_list.pop();
+        }
+    }
+    false
+}
+
+/// What the moves so far have to be checked against, to only search one of
+/// the move sequences that are the same up to a symmetry of the cube.
+///
+/// A solution turned by a symmetry of the cube solves it too, so of all of
+/// these only the one that comes first, comparing move by move, is searched.
+/// A cube that commutes with every move, which besides solved is only the
+/// superflip, is also solved by any rotation of a solution, where some moves
+/// are taken off the front and done at the end instead. The shortest
+/// solutions can never be shortened by joining the moves that meet, so
+/// these are compared the same way.
+///
+/// # Variables
+/// * `symmetries` - The symmetries of the cube that turn the moves so far
+///     into themselves, as a bit for each.
+/// * `cube_symmetries` - All the symmetries of the cube if it commutes with
+///     every move, otherwise none.
+/// * `rotations` - For each place `i` in the moves so far, the symmetries
+///     that turn the moves from `i` on into the same number of first moves.
+#[derive(Copy, Clone)]
+struct Reductions {
+    symmetries: u64,
+    cube_symmetries: u64,
+    rotations: [u64; MAX_OPTIMAL_DEPTH],
+}
+
+impl Reductions {
+    fn new(rubiks: &physical::Cube) -> Reductions {
+        let symmetries = (0..symmetry::N_SYM)
+            .filter(|s| symmetry::conjugate(rubiks, *s) == *rubiks)
+            .fold(0, |symmetries, s| symmetries | 1 << s);
+        let central = physical::basic_moves()
+            .iter()
+            .all(|turn| rubiks.multiply(turn) == turn.multiply(rubiks));
+        Reductions {
+            symmetries,
+            cube_symmetries: if central { symmetries } else { 0 },
+            rotations: [0; MAX_OPTIMAL_DEPTH],
+        }
+    }
+
+    /// The reductions after one more move.
+    ///
+    /// # Parameters
+    /// * `tables` - The `OptimalTables`.
+    /// * `move_list` - The moves so far.
+    /// * `movement` - The next move.
+    /// # Returns
+    /// * `Option<Reductions>` - What the moves have to be checked against
+    ///     after `movement`. `None` if a symmetry or rotation of them comes
+    ///     first, as that has already been searched or will be.
+    #[inline]
+    fn after(&self, tables: &OptimalTables, move_list: &[Moves], movement: Moves) -> Option<Reductions> {
+        let mut next = *self;
+        next.symmetries = matching_symmetries(tables, self.symmetries, movement, movement)?;
+        if self.cube_symmetries == 0 {
+            return Some(next);
+        }
+        let place = move_list.len();
+        for i in 1..place {
+            if self.rotations[i] != 0 {
+                next.rotations[i] = matching_symmetries(tables, self.rotations[i], movement, move_list[place - i])?;
+            }
+        }
+        if place > 0 {
+            next.rotations[place] = matching_symmetries(tables, self.cube_symmetries, movement, move_list[0])?;
+        }
+        Some(next)
+    }
+}
+
+/// The symmetries that turn one move into another.
+///
+/// # Parameters
+/// * `tables` - The `OptimalTables`.
+/// * `symmetries` - The symmetries to try, as a bit for each.
+/// * `movement` - The move to turn.
+/// * `target` - The move to compare it to.
+/// # Returns
+/// * `Option<u64>` - The symmetries that turn `movement` into `target`.
+///     `None` if one of them turns it into a move before `target`.
+#[inline]
+fn matching_symmetries(tables: &OptimalTables, symmetries: u64, movement: Moves, target: Moves) -> Option<u64> {
+    if symmetries == 1 && movement == target {
+        return Some(1);
+    }
+    let mut matching = 0;
+    let mut rest = symmetries;
+    while rest != 0 {
+        let s = rest.trailing_zeros() as usize;
+        rest = rest & (rest - 1);
+        let conjugated = tables.conjugate_moves[s][movement as usize] as usize;
+        if conjugated < target as usize {
+            return None;
+        }
+        if conjugated == target as usize {
+            matching = matching | 1 << s;
+        }
+    }
+    Some(matching)
+}
+
+/// Checks if a move list solves a cube.
+fn is_solved(rubiks: &physical::Cube, move_list: &[Moves]) -> bool {
+    let mut c = *rubiks;
+    for movement in move_list.iter() {
+        c = do_move(c, *movement);
+    }
+    c == physical::Cube::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use solver::test_util::{check, scrambled, setup, superflip};
+    use solver::CancelToken;
+
+    #[test]
+    fn test_optimal_solves_short_scrambles() {
+        use std::sync::mpsc;
+
+        setup();
+        assert!(optimal_search(&physical::Cube::new(), SolverContext::new()).unwrap().moves.is_empty());
+
+        let rubiks = scrambled(&[Moves::R1, Moves::U1, Moves::R3, Moves::U3]);
+        let (sender, receiver) = mpsc::channel();
+        let context = SolverContext::new().with_progress(move |progress| sender.send(progress.depth).unwrap());
+        let result = optimal_search(&rubiks, context).unwrap();
+        assert_eq!(result.moves.len(), 4);
+        assert_eq!(result.phase_one, result.moves);
+        assert!(result.phase_two.is_empty());
+        assert_eq!(receiver.iter().max(), Some(4));
+        check(&rubiks, &result.moves);
+
+        let scramble = [
+            Moves::F1, Moves::L2, Moves::D3, Moves::B1, Moves::R1, Moves::U2, Moves::L3, Moves::F2,
+        ];
+        let rubiks = scrambled(&scramble);
+        let solution = optimal_search(&rubiks, SolverContext::new()).unwrap().moves;
+        assert!(solution.len() <= scramble.len());
+        check(&rubiks, &solution);
+    }
+
+    #[test]
+    fn test_optimal_length() {
+        setup();
+
+        // A quarter turn of each face in turn, which cannot be done in fewer
+        // moves.
+        let scramble = [Moves::R1, Moves::U1, Moves::F1, Moves::L1, Moves::D1, Moves::B1];
+        let rubiks = scrambled(&scramble);
+        let solution = optimal_search(&rubiks, SolverContext::new()).unwrap().moves;
+        assert_eq!(solution.len(), 6);
+        check(&rubiks, &solution);
+    }
+
+    #[test]
+    fn test_optimal_search_errors() {
+        setup();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert_eq!(
+            optimal_search(&superflip(), SolverContext::new().with_cancel_token(cancel)),
+            Err(SolveError::Cancelled)
+        );
+        // Random states are about 18 moves from solved, far more than
+        // 10000 positions away.
+        let random = physical::Cube::random(&mut StdRng::seed_from_u64(7));
+        assert_eq!(
+            optimal_search(&random, SolverContext::new().with_node_limit(10_000)),
+            Err(SolveError::NodeLimit)
+        );
+
+        let mut twisted = physical::Cube::new();
+        twisted.corners.orientation[0] = 1;
+        assert_eq!(
+            optimal_search(&twisted, SolverContext::new()),
+            Err(SolveError::Unsolvable(physical::Unsolvable::CornerTwist))
+        );
+    }
+
+    // Proving there is no 19 move solution needs the huge tables, which take
+    // minutes to build, and then minutes more in a release build. Run it
+    // with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_optimal_superflip() {
+        setup();
+        let rubiks = superflip();
+        let solution = optimal_search(&rubiks, SolverContext::new()).unwrap().moves;
+        assert_eq!(solution.len(), 20);
+        check(&rubiks, &solution);
+    }
+}
@@ -0,0 +1,313 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! The budgeted two-phase search spread over several threads.
+//!
+//! Phase one only ever takes the cube into G1 seen from the UD axis, so how
+//! short the solutions are depends on which way up the cube is held. Each
+//! thread therefore works on one of six cubes: the cube turned so each of
+//! its three axes is the UD axis, and the inverse of each of those. Work is
+//! handed out a first move at a time, so every thread keeps busy whatever
+//! the cube. All the threads share the best solution so far, so a thread
+//! never looks for anything longer than what another has already found.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use physical::{self, symmetry};
+use solver::symmetry_tables;
+use solver::{inverse_move, Best, Moves, SolveError, SolveResult, SolverContext, Tracker, TwoPhase, PHASE_ONE_MOVE_LIST};
+
+/// The number of cubes searched, each axis with and without inverting.
+const N_ORIENTATIONS: usize = 6;
+
+/// One of the cubes the threads search.
+///
+/// # Variables
+/// * `symmetry` - The symmetry `S` the cube was turned by, so the cube is
+///     `S^-1 * X * S`.
+/// * `inverted` - Whether the cube was then inverted.
+/// * `two_phase` - The cube, ready to search.
+struct Orientation {
+    symmetry: usize,
+    inverted: bool,
+    two_phase: TwoPhase,
+}
+
+impl Orientation {
+    fn new(rubiks: &physical::Cube, i: usize) -> Orientation {
+        let symmetry = symmetry::AXES[i / 2];
+        let inverted = i % 2 == 1;
+        let mut c = symmetry::conjugate(rubiks, symmetry);
+        if inverted {
+            c = c.inverse();
+        }
+        Orientation {
+            symmetry,
+            inverted,
+            two_phase: TwoPhase::new(c),
+        }
+    }
+
+    /// Turns a solution of this cube into a solution of the original.
+    ///
+    /// A solution of the inverse, done backwards, solves the cube. A move
+    /// `m` on `S^-1 * X * S` is the move `S * m * S^-1` on `X`.
+    fn original_solution(&self, solution: &[Moves]) -> Vec<Moves> {
+        let symmetry_tables = symmetry_tables::tables();
+        let mut moves: Vec<Moves> = solution.to_vec();
+        if self.inverted {
+            moves = moves.iter().rev().map(|m| inverse_move(*m)).collect();
+        }
+        moves
+            .iter()
+            .map(|m| symmetry_tables.conjugate_move(*m, symmetry::inverse(self.symmetry)))
+            .collect()
+    }
+
+    /// Turns the two phases of a solution of this cube into a solution of
+    /// the original. Backwards, phase two comes first and does not take the
+    /// cube into G1, so a solution of an inverse is given as one phase.
+    fn original_phases(&self, phase_one: &[Moves], phase_two: &[Moves]) -> (Vec<Moves>, Vec<Moves>) {
+        if self.inverted {
+            (self.original_solution(&[phase_one, phase_two].concat()), Vec::new())
+        } else {
+            (self.original_solution(phase_one), self.original_solution(phase_two))
+        }
+    }
+}
+
+/// Finds a solution like `complete_search_with_budget`, but searches the
+/// cube from six sides at once on several threads.
+///
+/// # Parameters
+/// * `rubiks` - A `Cube` struct that we are trying to solve.
+/// * `threads` - How many threads to search with. At least one is used.
+/// * `context` - When to stop looking, shared by all the threads, where to
+///     report progress, and where to report every solution shorter than the
+///     ones before it, already turned back to fit `rubiks`.
+/// # Returns
+/// * `Result<SolveResult, SolveError>` - The shortest solution found, even
+///     if the context stopped the search after it. Otherwise why there is
+///     none: the cube cannot be solved, or the context stopped the search
+///     before any solution was found. `nodes` counts every thread.
+pub fn parallel_search(
+    rubiks: &physical::Cube,
+    threads: usize,
+    mut context: SolverContext,
+) -> Result<SolveResult, SolveError> {
+    rubiks.check_solvable()?;
+    let orientations: Vec<Orientation> = (0..N_ORIENTATIONS)
+        .map(|i| Orientation::new(rubiks, i))
+        .collect();
+    let min_depth = orientations
+        .iter()
+        .map(|o| o.two_phase.distance)
+        .min()
+        .unwrap();
+    let max_depth = orientations[0].two_phase.phase_one.max_depth;
+    let target_length = context.target_length.unwrap_or(0);
+
+    let best = Best::new();
+    let on_improvement = Mutex::new(context.improvement.take());
+    let next_job = AtomicUsize::new(0);
+
+    // The first thread keeps the original tracker, so it is the one that
+    // reports progress.
+    let mut trackers = vec![Tracker::from_context(context)];
+    for _ in 1..threads.max(1) {
+        let tracker = trackers[0].share();
+        trackers.push(tracker);
+    }
+
+    let start = trackers[0].start;
+    let finished: Vec<(u64, Option<SolveError>)> = thread::scope(|scope| {
+        let mut handles = Vec::new();
+        for mut tracker in trackers.into_iter() {
+            let (orientations, best, on_improvement, next_job) =
+                (&orientations, &best, &on_improvement, &next_job);
+            handles.push(scope.spawn(move || {
+                // Jobs go through every first move of every orientation at
+                // one phase one depth before moving on to the next depth.
+                loop {
+                    let job = next_job.fetch_add(1, Ordering::Relaxed);
+                    let depth = min_depth + job / (N_ORIENTATIONS * 18);
+                    if !best.can_improve(depth, max_depth) {
+                        break;
+                    }
+                    let orientation = &orientations[(job / 18) % N_ORIENTATIONS];
+                    let first_moves = match depth {
+                        // With no moves there is only one job to do.
+                        0 if job % 18 != 0 => continue,
+                        0 => &PHASE_ONE_MOVE_LIST[..0],
+                        _ => &PHASE_ONE_MOVE_LIST[job % 18..job % 18 + 1],
+                    };
+
+                    let mut stop = false;
+                    let stopped = orientation.two_phase.try_depth(
+                        depth,
+                        first_moves,
+                        best,
+                        &mut tracker,
+                        &mut |phase_one, phase_two| {
+                            let (phase_one, phase_two) = orientation.original_phases(phase_one, phase_two);
+                            let length = phase_one.len() + phase_two.len();
+                            // Hold the lock while offering, so the improvements
+                            // are passed on in the order they were kept.
+                            let mut on_improvement = on_improvement.lock().unwrap();
+                            if best.offer(&phase_one, &phase_two) {
+                                if let Some(ref mut report) = *on_improvement {
+                                    report(&[phase_one, phase_two].concat());
+                                }
+                            }
+                            stop = length <= target_length;
+                            stop
+                        },
+                    );
+                    if stop {
+                        tracker.stop_all();
+                    }
+                    if stopped {
+                        break;
+                    }
+                }
+                (tracker.nodes, tracker.stopped)
+            }));
+        }
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let nodes = finished.iter().map(|done| done.0).sum();
+    best.result(nodes, start.elapsed()).ok_or_else(|| {
+        finished
+            .iter()
+            .filter_map(|done| done.1)
+            .next()
+            .unwrap_or(orientations[0].two_phase.depth_exhausted())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solver::test_util::{check, scrambled, setup, superflip};
+    use solver::{do_move, CancelToken};
+
+    #[test]
+    fn test_orientations_map_back() {
+        setup();
+        let rubiks = scrambled(&[Moves::R1, Moves::U1, Moves::F3, Moves::L2, Moves::D1]);
+        for i in 0..N_ORIENTATIONS {
+            let orientation = Orientation::new(&rubiks, i);
+            let solution = ::solver::search(
+                &orientation.two_phase.phase_one,
+                orientation.two_phase.coordinates,
+            );
+            let mut c = orientation.two_phase.rubiks;
+            for movement in solution.iter() {
+                c = do_move(c, *movement);
+            }
+            let rest = ::solver::search(
+                &orientation.two_phase.phase_two,
+                orientation.two_phase.phase_two.coordinates(&c),
+            );
+            check(
+                &rubiks,
+                &orientation.original_solution(&[solution, rest].concat()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_search() {
+        use std::sync::mpsc;
+
+        setup();
+        let scramble = [
+            Moves::R1,
+            Moves::U2,
+            Moves::F3,
+            Moves::L1,
+            Moves::D2,
+            Moves::B1,
+            Moves::U3,
+            Moves::R2,
+            Moves::F1,
+            Moves::D3,
+            Moves::L2,
+            Moves::B3,
+            Moves::U1,
+            Moves::F2,
+            Moves::R3,
+            Moves::D1,
+            Moves::L3,
+            Moves::B2,
+            Moves::U2,
+            Moves::R1,
+        ];
+        let rubiks = scrambled(&scramble);
+        let (sender, receiver) = mpsc::channel();
+        let (progress_sender, progress_receiver) = mpsc::channel();
+        let context = SolverContext::new()
+            .with_node_limit(2_000_000)
+            .with_improvement(move |s| sender.send(s.len()).unwrap())
+            .with_progress(move |progress| progress_sender.send(progress.nodes).unwrap());
+        let result = parallel_search(&rubiks, 4, context).unwrap();
+        let lengths: Vec<usize> = receiver.iter().collect();
+        check(&rubiks, &result.moves);
+        check(&rubiks, &[&result.phase_one[..], &result.phase_two[..]].concat());
+        assert!(lengths.windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(*lengths.last().unwrap(), result.phase_one.len() + result.phase_two.len());
+        let nodes: Vec<u64> = progress_receiver.iter().collect();
+        assert!(nodes.windows(2).all(|w| w[0] <= w[1]));
+        assert!(*nodes.last().unwrap() > 0);
+
+        // Anything will do, so the first solution ends the search. Other
+        // threads may still be finishing one of their own when it does.
+        let (sender, receiver) = mpsc::channel();
+        let context = SolverContext::new()
+            .with_target_length(30)
+            .with_improvement(move |_| sender.send(()).unwrap());
+        let solution = parallel_search(&rubiks, 1, context).unwrap().moves;
+        check(&rubiks, &solution);
+        assert_eq!(receiver.iter().count(), 1);
+        let solution = parallel_search(&rubiks, 4, SolverContext::new().with_target_length(30)).unwrap().moves;
+        check(&rubiks, &solution);
+        assert!(solution.len() <= 30);
+
+        let solved = parallel_search(&physical::Cube::new(), 2, SolverContext::new().with_target_length(30));
+        assert!(solved.unwrap().moves.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_search_errors() {
+        setup();
+        let mut twisted = physical::Cube::new();
+        twisted.corners.orientation[0] = 1;
+        assert_eq!(
+            parallel_search(&twisted, 2, SolverContext::new()),
+            Err(SolveError::Unsolvable(physical::Unsolvable::CornerTwist))
+        );
+        let mut duplicated = physical::Cube::new();
+        duplicated.edges.permutation[0] = duplicated.edges.permutation[1];
+        assert_eq!(
+            parallel_search(&duplicated, 2, SolverContext::new()),
+            Err(SolveError::Unsolvable(physical::Unsolvable::MissingCubies))
+        );
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert_eq!(
+            parallel_search(&superflip(), 2, SolverContext::new().with_cancel_token(cancel)),
+            Err(SolveError::Cancelled)
+        );
+    }
+}
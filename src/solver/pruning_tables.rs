@@ -0,0 +1,457 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Pruning tables used as the IDA* heuristic.
+//!
+//! Each table holds the exact number of moves needed to solve a pair of
+//! coordinates. Solving the whole phase takes at least that many, so any
+//! branch whose table value is more than the moves left can be cut. Further
+//! explanation at (http://kociemba.org/math/pruning.htm)
+//!
+//! The main table of each phase pairs a symmetry-reduced coordinate with a
+//! raw one. These are far too big for a byte per entry, so they only keep
+//! each distance mod 3 in two bits. That is still enough to follow the exact
+//! distance, as every move changes it by at most one. With four bits an
+//! entry the distances of phase one fit whole, for looking up cubes that are
+//! not a move away from one whose distance is known.
+
+use std::sync::OnceLock;
+
+use solver::move_tables::{self, MoveTable};
+use solver::symmetry_tables::{self, ConjugationTable, SymCoordinate};
+use solver::table_file::{self, Storage, TableKind};
+use solver::{Moves, PHASE_ONE_MOVE_LIST, PHASE_TWO_MOVE_LIST};
+
+/// Marks an entry the breadth first search has not reached yet.
+const EMPTY: u8 = u8::MAX;
+
+/// The number of sorted UD slice values a G1 cube can have.
+pub const N_G1_SORTED_SLICE: usize = 24;
+
+/// A table of distances to solved for a pair of coordinates.
+///
+/// # Variables
+/// * `size_b` - The number of values the second coordinate can take.
+/// * `table` - The distance of every pair, at `a * size_b + b`.
+pub struct PruningTable {
+    size_b: usize,
+    table: Storage<u8>,
+}
+
+impl PruningTable {
+    /// Loads a pruning table from the table cache, generating it if needed.
+    ///
+    /// # Parameters
+    /// * `kind` - Which table this is in the cache.
+    /// * `a` - The `MoveTable` of the first coordinate.
+    /// * `b` - The `MoveTable` of the second coordinate.
+    /// * `size_b` - The number of values of the second coordinate the table
+    ///     covers. Can be less than `b.size()` if the moves never leave that
+    ///     range.
+    /// * `moves` - The `Moves` of the phase the table is for.
+    /// # Return
+    /// * `PruningTable`
+    pub fn new(
+        kind: TableKind,
+        a: &MoveTable,
+        b: &MoveTable,
+        size_b: usize,
+        moves: &[Moves],
+    ) -> PruningTable {
+        PruningTable {
+            size_b,
+            table: table_file::load_or_build(kind, a.size(), size_b, || {
+                PruningTable::generate(a, b, size_b, moves)
+            }),
+        }
+    }
+
+    /// Builds a pruning table with a breadth first search from solved,
+    /// one depth at a time.
+    fn generate(a: &MoveTable, b: &MoveTable, size_b: usize, moves: &[Moves]) -> Vec<u8> {
+        let size = a.size() * size_b;
+        let mut table = vec![EMPTY; size];
+        table[0] = 0;
+
+        let mut filled = 1;
+        let mut depth = 0;
+        while filled < size {
+            let mut found = 0;
+            for i in 0..size {
+                if table[i] != depth {
+                    continue;
+                }
+                let (coordinate_a, coordinate_b) = (i / size_b, i % size_b);
+                for movement in moves.iter() {
+                    let next = a.do_move(coordinate_a, *movement) * size_b
+                        + b.do_move(coordinate_b, *movement);
+                    if table[next] == EMPTY {
+                        table[next] = depth + 1;
+                        found = found + 1;
+                    }
+                }
+            }
+            if found == 0 {
+                break;
+            }
+            filled = filled + found;
+            depth = depth + 1;
+        }
+
+        table
+    }
+
+    /// The number of moves needed to solve both coordinates.
+    ///
+    /// # Parameters
+    /// * `a` - The first coordinate.
+    /// * `b` - The second coordinate.
+    /// # Return
+    /// * `usize` - A lower bound on the moves left in the phase.
+    #[inline]
+    pub fn distance(&self, a: usize, b: usize) -> usize {
+        self.table[a * self.size_b + b] as usize
+    }
+}
+
+/// A table of distances to solved, mod 3, for a symmetry-reduced coordinate
+/// paired with a raw one.
+///
+/// A cube is looked up by moving it to the representative of its class with
+/// symmetry `S`, and conjugating the raw coordinate by `S` to match. Entries
+/// are packed four to a byte. A table can instead take four bits an entry and
+/// keep the distances mod 15, which for phase one is the distance itself.
+///
+/// The largest value an entry can hold marks the entries the search has not
+/// reached yet, so it is also what the distances are kept modulo.
+///
+/// # Variables
+/// * `symmetric` - The symmetry-reduced coordinate.
+/// * `symmetric_move` - Where a move takes the symmetric coordinate's raw
+///     value.
+/// * `conjugation` - Conjugates the raw coordinate by a symmetry.
+/// * `raw` - The `MoveTable` of the raw coordinate.
+/// * `moves` - The `Moves` the table was built with.
+/// * `bits` - The bits of each entry, 2 or 4.
+/// * `table` - The distance mod 3, or mod 15, of every pair, at
+///     `class * raw.size() + raw`.
+pub struct SymPruningTable {
+    symmetric: &'static SymCoordinate,
+    symmetric_move: fn(usize, Moves) -> usize,
+    conjugation: &'static ConjugationTable,
+    raw: &'static MoveTable,
+    moves: &'static [Moves],
+    bits: usize,
+    table: Storage<u8>,
+}
+
+impl SymPruningTable {
+    /// Loads a symmetry pruning table from the table cache, generating it if
+    /// needed.
+    ///
+    /// # Parameters
+    /// * `kind` - Which table this is in the cache.
+    /// * `symmetric` - The symmetry-reduced coordinate.
+    /// * `symmetric_move` - Where a move takes the symmetric coordinate's raw
+    ///     value.
+    /// * `conjugation` - Conjugates the raw coordinate by a symmetry.
+    /// * `raw` - The `MoveTable` of the raw coordinate.
+    /// * `moves` - The `Moves` to build the table with.
+    /// * `bits` - The bits of each entry: 2 to keep the distances mod 3, or
+    ///     4 to keep them mod 15.
+    /// # Return
+    /// * `SymPruningTable`
+    pub fn new(
+        kind: TableKind,
+        symmetric: &'static SymCoordinate,
+        symmetric_move: fn(usize, Moves) -> usize,
+        conjugation: &'static ConjugationTable,
+        raw: &'static MoveTable,
+        moves: &'static [Moves],
+        bits: usize,
+    ) -> SymPruningTable {
+        let mut table = SymPruningTable {
+            symmetric,
+            symmetric_move,
+            conjugation,
+            raw,
+            moves,
+            bits,
+            table: Storage::Owned(Vec::new()),
+        };
+        let bytes = (symmetric.len() * raw.size() * bits + 7) / 8;
+        table.table = table_file::load_or_build(kind, bytes, 1, || table.generate());
+        table
+    }
+
+    /// Builds the table with a breadth first search from solved.
+    ///
+    /// Early on each depth is found by expanding the entries of the depth
+    /// before. Once most of the table is filled it is quicker to go the
+    /// other way, and check every empty entry for a neighbour at the
+    /// previous depth.
+    ///
+    /// A move takes the representative of a class to the same class and
+    /// symmetry whatever the raw coordinate, so these are worked out once
+    /// for each class. Every entry reached from a class is then in one of
+    /// a few rows of the table, which keeps the big tables in the cache.
+    fn generate(&self) -> Vec<u8> {
+        let size_raw = self.raw.size();
+        let size = self.symmetric.len() * size_raw;
+        let (bits, empty) = (self.bits, self.modulus() as u8);
+        let mut table = vec![0xff; (size * bits + 7) / 8];
+        set(&mut table, bits, 0, 0);
+
+        let mut neighbours = vec![(0, 0); self.moves.len()];
+        let mut filled = 1;
+        let mut found = 1;
+        let mut depth = 0;
+        while filled < size {
+            let backwards = found * 3 > size - filled;
+            let depth_mod = (depth % self.modulus()) as u8;
+            let next_mod = ((depth + 1) % self.modulus()) as u8;
+            let wanted = if backwards { empty } else { depth_mod };
+            found = 0;
+
+            for class in 0..self.symmetric.len() {
+                let row = class * size_raw;
+                if !(row..row + size_raw).any(|i| get(&table, bits, i) == wanted) {
+                    continue;
+                }
+                let representative = self.symmetric.representative(class);
+                for (m, movement) in self.moves.iter().enumerate() {
+                    let next_symmetric = (self.symmetric_move)(representative, *movement);
+                    neighbours[m] = (
+                        self.symmetric.class(next_symmetric),
+                        self.symmetric.symmetry(next_symmetric),
+                    );
+                }
+
+                for coordinate in 0..size_raw {
+                    let i = row + coordinate;
+                    if get(&table, bits, i) != wanted {
+                        continue;
+                    }
+
+                    for (m, movement) in self.moves.iter().enumerate() {
+                        let (next_class, s) = neighbours[m];
+                        let next = self.conjugation.get(self.raw.do_move(coordinate, *movement), s);
+                        let j = next_class * size_raw + next;
+                        if backwards {
+                            if get(&table, bits, j) == depth_mod {
+                                set(&mut table, bits, i, next_mod);
+                                found = found + 1;
+                                break;
+                            }
+                        } else if get(&table, bits, j) == empty {
+                            set(&mut table, bits, j, next_mod);
+                            found = found + 1;
+
+                            // Entries that are the same cube seen through a
+                            // symmetry of the representative are just as far
+                            // away, but no move will ever lead to them.
+                            let self_symmetries = self.symmetric.self_symmetries(next_class);
+                            for s in 1..16 {
+                                if (self_symmetries >> s) & 1 == 0 {
+                                    continue;
+                                }
+                                let k = next_class * size_raw + self.conjugation.get(next, s);
+                                if get(&table, bits, k) == empty {
+                                    set(&mut table, bits, k, next_mod);
+                                    found = found + 1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if found == 0 {
+                break;
+            }
+            filled = filled + found;
+            depth = depth + 1;
+        }
+
+        table
+    }
+
+    /// Splits a pair of coordinates into the class of the first and the
+    /// second conjugated to match the class representative.
+    #[inline]
+    fn index_parts(&self, symmetric: usize, raw: usize) -> (usize, usize) {
+        let s = self.symmetric.symmetry(symmetric);
+        (self.symmetric.class(symmetric), self.conjugation.get(raw, s))
+    }
+
+    /// What distances are kept modulo: 3 with two bits an entry, 15 with
+    /// four.
+    #[inline]
+    fn modulus(&self) -> usize {
+        (1 << self.bits) - 1
+    }
+
+    /// The number of moves needed to solve a pair of coordinates, mod 3, or
+    /// mod 15 for a table with four bits an entry.
+    ///
+    /// # Parameters
+    /// * `symmetric` - The raw value of the symmetry-reduced coordinate.
+    /// * `raw` - The raw coordinate.
+    /// # Return
+    /// * `usize` - The distance to solved, mod the table's modulus.
+    #[inline]
+    pub fn distance_mod(&self, symmetric: usize, raw: usize) -> usize {
+        let (class, raw) = self.index_parts(symmetric, raw);
+        get(&self.table, self.bits, class * self.raw.size() + raw) as usize
+    }
+
+    /// The exact number of moves needed to solve a pair of coordinates.
+    ///
+    /// The table only stores this mod 3, so we walk down to solved one move
+    /// at a time, always to a neighbour one move closer. Tables that keep
+    /// the whole distance are better read with `distance_mod`.
+    ///
+    /// # Parameters
+    /// * `symmetric` - The raw value of the symmetry-reduced coordinate.
+    /// * `raw` - The raw coordinate.
+    /// # Return
+    /// * `usize` - The distance to solved.
+    pub fn distance(&self, symmetric: usize, raw: usize) -> usize {
+        let (mut symmetric, mut raw) = (symmetric, raw);
+        let mut distance = 0;
+        while symmetric != 0 || raw != 0 {
+            let closer = (self.distance_mod(symmetric, raw) + self.modulus() - 1) % self.modulus();
+            for movement in self.moves.iter() {
+                let next_symmetric = (self.symmetric_move)(symmetric, *movement);
+                let next_raw = self.raw.do_move(raw, *movement);
+                if self.distance_mod(next_symmetric, next_raw) == closer {
+                    symmetric = next_symmetric;
+                    raw = next_raw;
+                    break;
+                }
+            }
+            distance = distance + 1;
+        }
+        distance
+    }
+
+    /// The distance of a neighbour, given the distance of the pair it is a
+    /// move away from.
+    ///
+    /// # Parameters
+    /// * `distance` - The exact distance before the move.
+    /// * `symmetric` - The symmetry-reduced coordinate after the move.
+    /// * `raw` - The raw coordinate after the move.
+    /// # Return
+    /// * `usize` - The exact distance after the move.
+    #[inline]
+    pub fn next_distance(&self, distance: usize, symmetric: usize, raw: usize) -> usize {
+        let modulus = self.modulus();
+        match (self.distance_mod(symmetric, raw) + modulus - distance % modulus) % modulus {
+            0 => distance,
+            1 => distance + 1,
+            _ => distance - 1,
+        }
+    }
+}
+
+/// Reads an entry of a packed table with `bits` bits an entry.
+#[inline]
+fn get(table: &[u8], bits: usize, i: usize) -> u8 {
+    let per_byte = 8 / bits;
+    (table[i / per_byte] >> (bits * (i % per_byte))) & ((1 << bits) - 1)
+}
+
+/// Writes an entry of a packed table with `bits` bits an entry.
+#[inline]
+fn set(table: &mut [u8], bits: usize, i: usize, value: u8) {
+    let per_byte = 8 / bits;
+    let shift = bits * (i % per_byte);
+    let mask = ((1 << bits) - 1) << shift;
+    table[i / per_byte] = (table[i / per_byte] & !mask) | (value << shift);
+}
+
+/// Every pruning table the two-phase search needs.
+///
+/// # Variables
+/// * `flip_slice_twist` - The flip and UD slice classes with the corner
+///     twist, for phase one.
+/// * `corner_edge_permutation` - The corner permutation classes with the U
+///     and D edge permutation, for phase two.
+/// * `corner_sorted_slice` - The corner permutation with the sorted UD
+///     slice, a second bound for phase two.
+pub struct PruningTables {
+    pub flip_slice_twist: SymPruningTable,
+    pub corner_edge_permutation: SymPruningTable,
+    pub corner_sorted_slice: PruningTable,
+}
+
+impl PruningTables {
+    /// Generates all the pruning tables.
+    ///
+    /// # Return
+    /// * `PruningTables`
+    pub fn new() -> PruningTables {
+        let tables = move_tables::tables();
+        let symmetry = symmetry_tables::tables();
+        PruningTables {
+            flip_slice_twist: SymPruningTable::new(
+                TableKind::FlipSliceTwistPruning,
+                &symmetry.flip_slice,
+                flip_slice_move,
+                &symmetry.twist,
+                &tables.twist,
+                &PHASE_ONE_MOVE_LIST,
+                2,
+            ),
+            corner_edge_permutation: SymPruningTable::new(
+                TableKind::CornerEdgePruning,
+                &symmetry.corner_permutation,
+                |corners, movement| {
+                    move_tables::tables().corner_permutation.do_move(corners, movement)
+                },
+                &symmetry.edge_permutation,
+                &tables.edge_permutation,
+                &PHASE_TWO_MOVE_LIST,
+                2,
+            ),
+            corner_sorted_slice: PruningTable::new(
+                TableKind::CornerSortedSlicePruning,
+                &tables.corner_permutation,
+                &tables.sorted_slice,
+                N_G1_SORTED_SLICE,
+                &PHASE_TWO_MOVE_LIST,
+            ),
+        }
+    }
+}
+
+/// Where a move takes the joined flip and UD slice coordinate of phase one,
+/// `slice * N_FLIP + flip`.
+///
+/// # Parameters
+/// * `flip_slice` - The flip and slice before the move.
+/// * `movement` - The `Moves` to do.
+/// # Return
+/// * `usize` - The flip and slice after the move.
+pub fn flip_slice_move(flip_slice: usize, movement: Moves) -> usize {
+    let tables = move_tables::tables();
+    let (slice, flip) = (flip_slice / move_tables::N_FLIP, flip_slice % move_tables::N_FLIP);
+    tables.slice.do_move(slice, movement) * move_tables::N_FLIP + tables.flip.do_move(flip, movement)
+}
+
+/// The pruning tables shared by every search, generated on first use.
+///
+/// # Return
+/// * `&'static PruningTables`
+pub fn tables() -> &'static PruningTables {
+    static TABLES: OnceLock<PruningTables> = OnceLock::new();
+    TABLES.get_or_init(PruningTables::new)
+}
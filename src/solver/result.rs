@@ -0,0 +1,244 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! What a solve gives back: the solution and how it was found, or why there
+//! is none.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use physical::Unsolvable;
+use solver::{face, Moves, Notation, ALL_MOVES};
+
+/// A solution found by a search.
+///
+/// # Variables
+/// * `phase_one` - The moves taking the cube into G1. The parallel search
+///     may find it from another axis, so into G1 as seen from there.
+///     Searches that are not split into phases, such as the optimal search
+///     or the parallel search working on the inverse, give every move here.
+/// * `phase_two` - The moves solving the cube from there. Empty if the
+///     search was not split into phases.
+/// * `moves` - Both phases together, with the moves where they meet merged.
+/// * `nodes` - Positions visited over both phases.
+/// * `elapsed` - How long the search took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveResult {
+    pub phase_one: Vec<Moves>,
+    pub phase_two: Vec<Moves>,
+    pub moves: Vec<Moves>,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+impl SolveResult {
+    /// The length in the half-turn metric, where every turn of a face counts
+    /// as one move. A slice move is two, and a rotation none.
+    pub fn half_turn_metric(&self) -> usize {
+        self.moves.iter().map(|m| layer_turns(*m)).sum()
+    }
+
+    /// The length in the quarter-turn metric, where half turns count as two
+    /// moves.
+    pub fn quarter_turn_metric(&self) -> usize {
+        self.moves
+            .iter()
+            .map(|m| if *m as usize % 3 == 1 { 2 * layer_turns(*m) } else { layer_turns(*m) })
+            .sum()
+    }
+
+    /// The length in the slice-turn metric, where any one layer turn counts
+    /// as one move, and so does turning two opposite faces the same way as
+    /// a slice move would.
+    pub fn slice_turn_metric(&self) -> usize {
+        let mut length = 0;
+        let mut i = 0;
+        while i < self.moves.len() {
+            if i + 1 < self.moves.len() && is_slice_turn(self.moves[i], self.moves[i + 1]) {
+                i = i + 1;
+            }
+            if !is_rotation(self.moves[i]) {
+                length = length + 1;
+            }
+            i = i + 1;
+        }
+        length
+    }
+}
+
+impl fmt::Display for SolveResult {
+    /// Writes the moves in Singmaster notation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Notation::new(&self.moves))
+    }
+}
+
+/// Why a solve found no solution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The cube cannot be reached from a solved cube.
+    Unsolvable(Unsolvable),
+    /// A phase found no solution within its maximum depth. Searches that are
+    /// not split into phases give phase 0.
+    DepthExhausted { phase: usize, max_depth: usize },
+    /// The search was cancelled through its `CancelToken`.
+    Cancelled,
+    /// The search ran past its deadline.
+    TimedOut,
+    /// The search visited as many positions as it was allowed to.
+    NodeLimit,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SolveError::Unsolvable(reason) => write!(f, "The cube cannot be solved: {}.", reason),
+            SolveError::DepthExhausted { phase: 0, max_depth } => {
+                write!(f, "The search found no solution within {} moves.", max_depth)
+            }
+            SolveError::DepthExhausted { phase, max_depth } => write!(
+                f,
+                "Phase {} found no solution within {} moves.",
+                phase, max_depth
+            ),
+            SolveError::Cancelled => write!(f, "The search was cancelled."),
+            SolveError::TimedOut => write!(f, "The search ran out of time."),
+            SolveError::NodeLimit => write!(f, "The search visited as many positions as it was allowed to."),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<Unsolvable> for SolveError {
+    fn from(reason: Unsolvable) -> SolveError {
+        SolveError::Unsolvable(reason)
+    }
+}
+
+/// Merges turns of the same face that end up next to each other, allowing
+/// for turns of the opposite face between them, and drops any that cancel
+/// out.
+///
+/// # Parameters
+/// * `moves` - The moves to simplify.
+/// # Returns
+/// * `Vec<Moves>` - Moves doing the same as `moves`, never longer.
+pub fn simplify(moves: &[Moves]) -> Vec<Moves> {
+    let mut simplified: Vec<Moves> = Vec::new();
+    for movement in moves.iter() {
+        if *movement == Moves::NONE {
+            continue;
+        }
+        let n = simplified.len();
+        // Where a turn of the same layer is, if it can be merged with.
+        let same_layer = if n > 0 && layer(simplified[n - 1]) == layer(*movement) {
+            Some(n - 1)
+        } else if n > 1
+            && face(*movement) < 6
+            && face(simplified[n - 1]) == opposite(face(*movement))
+            && layer(simplified[n - 2]) == layer(*movement)
+        {
+            Some(n - 2)
+        } else {
+            None
+        };
+
+        match same_layer {
+            Some(i) => {
+                let turns = (quarter_turns(simplified[i]) + quarter_turns(*movement)) % 4;
+                if turns == 0 {
+                    simplified.remove(i);
+                } else {
+                    simplified[i] = ALL_MOVES[layer(*movement) * 3 + turns - 1];
+                }
+            }
+            None => simplified.push(*movement),
+        }
+    }
+    simplified
+}
+
+/// The layer a move turns, as laid out in `Moves`.
+fn layer(movement: Moves) -> usize {
+    movement as usize / 3
+}
+
+/// How many clockwise quarter turns a move is.
+fn quarter_turns(movement: Moves) -> usize {
+    movement as usize % 3 + 1
+}
+
+/// The face opposite `face`, both as given by `solver::face`.
+fn opposite(face: usize) -> usize {
+    face ^ 1
+}
+
+fn is_rotation(movement: Moves) -> bool {
+    (9..12).contains(&layer(movement))
+}
+
+/// How many outer layer turns a move counts as: two for a slice move, none
+/// for a rotation and one for anything else.
+fn layer_turns(movement: Moves) -> usize {
+    match layer(movement) {
+        6..=8 => 2,
+        9..=11 => 0,
+        _ => 1,
+    }
+}
+
+/// Whether two moves turn opposite faces the same way, and so together turn
+/// the slice between them.
+fn is_slice_turn(a: Moves, b: Moves) -> bool {
+    face(a) < 6 && face(a) == opposite(face(b)) && (quarter_turns(a) + quarter_turns(b)) % 4 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(simplify(&[Moves::U1, Moves::U2]), vec![Moves::U3]);
+        assert_eq!(simplify(&[Moves::R1, Moves::U1, Moves::U3, Moves::R1]), vec![Moves::R2]);
+        assert_eq!(simplify(&[Moves::U1, Moves::D2, Moves::U1]), vec![Moves::U2, Moves::D2]);
+        assert_eq!(simplify(&[Moves::F2, Moves::B1, Moves::F2, Moves::B3]), vec![]);
+        assert_eq!(simplify(&[Moves::M1, Moves::M1, Moves::X3]), vec![Moves::M2, Moves::X3]);
+        assert_eq!(simplify(&[Moves::X1, Moves::L1, Moves::X1]), vec![Moves::X1, Moves::L1, Moves::X1]);
+        assert_eq!(
+            simplify(&[Moves::R1, Moves::U1, Moves::R1]),
+            vec![Moves::R1, Moves::U1, Moves::R1]
+        );
+    }
+
+    #[test]
+    fn test_metrics() {
+        let result = SolveResult {
+            phase_one: vec![],
+            phase_two: vec![],
+            moves: vec![Moves::R1, Moves::L3, Moves::U2, Moves::D2, Moves::F2],
+            nodes: 0,
+            elapsed: Duration::from_secs(0),
+        };
+        assert_eq!(result.half_turn_metric(), 5);
+        assert_eq!(result.quarter_turn_metric(), 8);
+        assert_eq!(result.slice_turn_metric(), 3);
+
+        let result = SolveResult {
+            moves: vec![Moves::M2, Moves::U1, Moves::X1, Moves::Rw3],
+            ..result
+        };
+        assert_eq!(result.half_turn_metric(), 4);
+        assert_eq!(result.quarter_turn_metric(), 6);
+        assert_eq!(result.slice_turn_metric(), 3);
+    }
+}
@@ -0,0 +1,92 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Random-state scrambles, as used in competitions.
+//!
+//! Turning a cube at random does not make every state equally likely, so
+//! instead a state is picked at random and solved. Doing the solution
+//! backwards from a solved cube then gives that state.
+
+use std::fmt;
+
+use rand::Rng;
+
+use physical;
+use solver::{complete_search, inverse_move, Moves, Notation, SolveError};
+
+/// A scramble and the cube it leads to.
+///
+/// # Variables
+/// * `cube` - The random state.
+/// * `moves` - Moves taking a solved cube to `cube`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scramble {
+    pub cube: physical::Cube,
+    pub moves: Vec<Moves>,
+}
+
+impl fmt::Display for Scramble {
+    /// Writes the moves in Singmaster notation.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Notation::new(&self.moves))
+    }
+}
+
+/// Makes a random-state scramble.
+///
+/// States whose solution comes out shorter than `min_length` are thrown
+/// away and another is picked. The two-phase solutions are rarely under
+/// 18 moves, so asking for much more than that can take a long time.
+///
+/// # Parameters
+/// * `rng` - Where the randomness comes from. Seed it for the same
+///     scramble every time.
+/// * `min_length` - The fewest moves the scramble may have.
+/// # Returns
+/// * `Result<Scramble, SolveError>` - The scramble, or why the state picked
+///     could not be solved.
+pub fn random_state_scramble<R: Rng + ?Sized>(rng: &mut R, min_length: usize) -> Result<Scramble, SolveError> {
+    loop {
+        let cube = physical::Cube::random(rng);
+        let solution = complete_search(&cube)?;
+        if solution.moves.len() >= min_length {
+            let moves = solution.moves.iter().rev().map(|m| inverse_move(*m)).collect();
+            return Ok(Scramble { cube, moves });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use solver::do_move;
+    use solver::test_util::setup;
+
+    #[test]
+    fn test_random_state_scramble() {
+        setup();
+        let mut rng = StdRng::seed_from_u64(2018);
+        for _ in 0..3 {
+            let scramble = random_state_scramble(&mut rng, 10).unwrap();
+            assert!(scramble.moves.len() >= 10);
+            let mut c = physical::Cube::new();
+            for movement in scramble.moves.iter() {
+                c = do_move(c, *movement);
+            }
+            assert_eq!(c, scramble.cube);
+        }
+
+        let a = random_state_scramble(&mut StdRng::seed_from_u64(5), 0).unwrap();
+        let b = random_state_scramble(&mut StdRng::seed_from_u64(5), 0).unwrap();
+        assert_eq!(a.to_string(), b.to_string());
+    }
+}
@@ -0,0 +1,346 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Symmetry-reduced coordinates.
+//!
+//! Two cubes that are the same up to one of the 16 symmetries that keep the
+//! UD axis fixed are the same distance from G1, and from solved within G1.
+//! Grouping raw coordinates into these equivalence classes lets a pruning
+//! table store one entry per class instead of one per raw value, which is
+//! about 16 times smaller. Further explanation at
+//! (http://kociemba.org/math/symmetric.htm)
+
+use std::sync::OnceLock;
+
+use physical::{self, symmetry};
+use solver::move_tables;
+use solver::table_file::{self, Storage, TableKind};
+use solver::{do_move, Moves, PHASE_ONE_MOVE_LIST};
+
+pub const N_FLIP_SLICE: usize = move_tables::N_FLIP * move_tables::N_SLICE;
+pub const N_FLIP_SLICE_CLASS: usize = 64430;
+pub const N_CORNER_CLASS: usize = 2768;
+pub const N_FLIP_SORTED_SLICE: usize = move_tables::N_FLIP * move_tables::N_SORTED_SLICE;
+pub const N_FLIP_SORTED_SLICE_CLASS: usize = 1523864;
+
+/// A raw coordinate split into symmetry classes.
+///
+/// Every class has one raw value picked as its representative. Any other
+/// raw value `r` in the class is `S^-1 * rep * S` for some symmetry `S`.
+///
+/// # Variables
+/// * `classes` - For each raw value, `class * 16 + S`.
+/// * `representatives` - For each class, the raw value representing it.
+/// * `self_symmetries` - For each class, a bit mask of the symmetries that
+///     leave its representative unchanged.
+pub struct SymCoordinate {
+    classes: Storage<u32>,
+    representatives: Storage<u32>,
+    self_symmetries: Storage<u16>,
+}
+
+impl SymCoordinate {
+    /// Loads a symmetry coordinate from the table cache, generating it if
+    /// needed.
+    ///
+    /// # Parameters
+    /// * `kinds` - The cached tables for the classes, representatives and
+    ///     self symmetries.
+    /// * `n_raw` - The number of raw values.
+    /// * `n_class` - The number of classes.
+    /// * `conjugate` - Gives the raw value of `S^-1 * raw * S`.
+    /// # Return
+    /// * `SymCoordinate`
+    pub fn new(
+        kinds: [TableKind; 3],
+        n_raw: usize,
+        n_class: usize,
+        conjugate: &dyn Fn(usize, usize) -> usize,
+    ) -> SymCoordinate {
+        let mut generated: Option<(Vec<u32>, Vec<u32>, Vec<u16>)> = None;
+        let mut generate = || {
+            if generated.is_none() {
+                generated = Some(SymCoordinate::generate(n_raw, n_class, conjugate));
+            }
+            generated.clone().unwrap()
+        };
+
+        let classes = table_file::load_or_build(kinds[0], n_raw, 1, || generate().0);
+        let representatives = table_file::load_or_build(kinds[1], n_class, 1, || generate().1);
+        let self_symmetries = table_file::load_or_build(kinds[2], n_class, 1, || generate().2);
+        SymCoordinate {
+            classes,
+            representatives,
+            self_symmetries,
+        }
+    }
+
+    /// Sorts every raw value into a class. The first raw value of a class
+    /// to be found becomes its representative.
+    fn generate(
+        n_raw: usize,
+        n_class: usize,
+        conjugate: &dyn Fn(usize, usize) -> usize,
+    ) -> (Vec<u32>, Vec<u32>, Vec<u16>) {
+        let mut classes = vec![u32::MAX; n_raw];
+        let mut representatives = Vec::with_capacity(n_class);
+        let mut self_symmetries = Vec::with_capacity(n_class);
+
+        for raw in 0..n_raw {
+            if classes[raw] != u32::MAX {
+                continue;
+            }
+            let class = representatives.len() as u32;
+            representatives.push(raw as u32);
+
+            let mut self_symmetry = 0_u16;
+            for s in 0..symmetry::N_SYM_D4H {
+                let conjugated = conjugate(raw, s);
+                if classes[conjugated] == u32::MAX {
+                    classes[conjugated] = class * symmetry::N_SYM_D4H as u32 + s as u32;
+                }
+                if conjugated == raw {
+                    self_symmetry = self_symmetry | (1 << s);
+                }
+            }
+            self_symmetries.push(self_symmetry);
+        }
+
+        assert_eq!(representatives.len(), n_class);
+        (classes, representatives, self_symmetries)
+    }
+
+    /// The class a raw value belongs to.
+    #[inline]
+    pub fn class(&self, raw: usize) -> usize {
+        self.classes[raw] as usize / symmetry::N_SYM_D4H
+    }
+
+    /// The symmetry `S` with `raw = S^-1 * rep * S`, so conjugating by `S`
+    /// the other way takes `raw` back to its representative.
+    #[inline]
+    pub fn symmetry(&self, raw: usize) -> usize {
+        self.classes[raw] as usize % symmetry::N_SYM_D4H
+    }
+
+    /// The raw value representing a class.
+    #[inline]
+    pub fn representative(&self, class: usize) -> usize {
+        self.representatives[class] as usize
+    }
+
+    /// A bit mask of the symmetries that leave a class's representative
+    /// unchanged. Bit 0, the identity, is always set.
+    #[inline]
+    pub fn self_symmetries(&self, class: usize) -> u16 {
+        self.self_symmetries[class]
+    }
+
+    /// The number of classes.
+    pub fn len(&self) -> usize {
+        self.representatives.len()
+    }
+}
+
+/// A coordinate conjugated by each of the 16 UD symmetries, as
+/// `S * c * S^-1`. Used to carry a second coordinate along when the first
+/// is moved to its class representative.
+pub struct ConjugationTable {
+    table: Storage<u16>,
+}
+
+impl ConjugationTable {
+    /// Loads a conjugation table from the table cache, generating it if
+    /// needed.
+    ///
+    /// # Parameters
+    /// * `kind` - Which table this is in the cache.
+    /// * `size` - The number of values the coordinate can take.
+    /// * `conjugate` - Gives the coordinate of `S * c * S^-1`.
+    /// # Return
+    /// * `ConjugationTable`
+    fn new(kind: TableKind, size: usize, conjugate: &dyn Fn(usize, usize) -> usize) -> ConjugationTable {
+        ConjugationTable {
+            table: table_file::load_or_build(kind, size, symmetry::N_SYM_D4H, || {
+                let mut table = Vec::with_capacity(size * symmetry::N_SYM_D4H);
+                for c in 0..size {
+                    for s in 0..symmetry::N_SYM_D4H {
+                        table.push(conjugate(c, s) as u16);
+                    }
+                }
+                table
+            }),
+        }
+    }
+
+    /// Looks up `S * c * S^-1`.
+    #[inline]
+    pub fn get(&self, coordinate: usize, s: usize) -> usize {
+        self.table[coordinate * symmetry::N_SYM_D4H + s] as usize
+    }
+}
+
+/// Every symmetry table the search needs.
+///
+/// # Variables
+/// * `flip_slice` - The flip and UD slice together, as
+///     `slice * N_FLIP + flip`, reduced to 64430 classes.
+/// * `corner_permutation` - The corner permutation, reduced to 2768
+///     classes.
+/// * `twist` - The corner twist conjugated by each symmetry.
+/// * `edge_permutation` - The phase two edge permutation conjugated by
+///     each symmetry.
+/// * `moves` - For each of the 48 symmetries `S` and each move `m`, the
+///     move `S^-1 * m * S`.
+pub struct SymmetryTables {
+    pub flip_slice: SymCoordinate,
+    pub corner_permutation: SymCoordinate,
+    pub twist: ConjugationTable,
+    pub edge_permutation: ConjugationTable,
+    moves: Vec<[Moves; 18]>,
+}
+
+impl SymmetryTables {
+    /// Generates all the symmetry tables.
+    ///
+    /// # Return
+    /// * `SymmetryTables`
+    pub fn new() -> SymmetryTables {
+        let flip_slice = SymCoordinate::new(
+            [
+                TableKind::FlipSliceClass,
+                TableKind::FlipSliceRepresentative,
+                TableKind::FlipSliceSelfSymmetry,
+            ],
+            N_FLIP_SLICE,
+            N_FLIP_SLICE_CLASS,
+            &|raw, s| {
+                let mut c = physical::Cube::new();
+                c.set_ud_slice((raw / move_tables::N_FLIP) as i32);
+                c.set_edge_orientation((raw % move_tables::N_FLIP) as i32);
+                c.edges = symmetry::conjugate_edges(&c.edges, s);
+                c.ud_slice() as usize * move_tables::N_FLIP + c.edge_orientation() as usize
+            },
+        );
+
+        let corner_permutation = SymCoordinate::new(
+            [
+                TableKind::CornerClass,
+                TableKind::CornerRepresentative,
+                TableKind::CornerSelfSymmetry,
+            ],
+            move_tables::N_CORNER_PERMUTATION,
+            N_CORNER_CLASS,
+            &|raw, s| {
+                let mut c = physical::Cube::new();
+                c.set_corner_permutation(raw as i32);
+                c.corners = symmetry::conjugate_corners(&c.corners, s);
+                c.corner_permutation() as usize
+            },
+        );
+
+        let twist = ConjugationTable::new(TableKind::TwistConjugation, move_tables::N_TWIST, &|t, s| {
+            let mut c = physical::Cube::new();
+            c.set_corner_orientation(t as i32);
+            c.corners = symmetry::conjugate_corners(&c.corners, symmetry::inverse(s));
+            c.corner_orientation() as usize
+        });
+
+        let edge_permutation = ConjugationTable::new(
+            TableKind::EdgePermutationConjugation,
+            move_tables::N_EDGE_PERMUTATION,
+            &|e, s| {
+                let mut c = physical::Cube::new();
+                c.set_phase_two_edge_permutation(e as i32);
+                c.edges = symmetry::conjugate_edges(&c.edges, symmetry::inverse(s));
+                c.phase_two_edge_permutation() as usize
+            },
+        );
+
+        let move_cubes: Vec<physical::Cube> = PHASE_ONE_MOVE_LIST
+            .iter()
+            .map(|m| do_move(physical::Cube::new(), *m))
+            .collect();
+        let mut moves = Vec::with_capacity(symmetry::N_SYM);
+        for s in 0..symmetry::N_SYM {
+            let mut conjugated = [Moves::NONE; 18];
+            for m in 0..18 {
+                let c = symmetry::conjugate(&move_cubes[m], s);
+                let j = move_cubes
+                    .iter()
+                    .position(|x| *x == c)
+                    .unwrap();
+                conjugated[m] = PHASE_ONE_MOVE_LIST[j];
+            }
+            moves.push(conjugated);
+        }
+
+        SymmetryTables {
+            flip_slice,
+            corner_permutation,
+            twist,
+            edge_permutation,
+            moves,
+        }
+    }
+
+    /// The move that does to a conjugated cube what `movement` does to the
+    /// cube itself.
+    ///
+    /// # Parameters
+    /// * `movement` - A move other than `Moves::NONE`.
+    /// * `s` - A symmetry between 0 and 47.
+    /// # Return
+    /// * `Moves` - The move `S^-1 * movement * S`.
+    #[inline]
+    pub fn conjugate_move(&self, movement: Moves, s: usize) -> Moves {
+        self.moves[s][movement as usize]
+    }
+}
+
+/// The symmetry tables shared by every search, generated on first use.
+///
+/// # Return
+/// * `&'static SymmetryTables`
+pub fn tables() -> &'static SymmetryTables {
+    static TABLES: OnceLock<SymmetryTables> = OnceLock::new();
+    TABLES.get_or_init(SymmetryTables::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solver::test_util::setup;
+
+    #[test]
+    fn test_classes() {
+        setup();
+        let tables = tables();
+        assert_eq!(tables.flip_slice.len(), N_FLIP_SLICE_CLASS);
+        assert_eq!(tables.corner_permutation.len(), N_CORNER_CLASS);
+
+        // Conjugating a raw value by its symmetry gives its representative.
+        for raw in (0..move_tables::N_CORNER_PERMUTATION).step_by(97) {
+            let class = tables.corner_permutation.class(raw);
+            let mut c = physical::Cube::new();
+            c.set_corner_permutation(tables.corner_permutation.representative(class) as i32);
+            c.corners = symmetry::conjugate_corners(&c.corners, tables.corner_permutation.symmetry(raw));
+            assert_eq!(c.corner_permutation() as usize, raw);
+        }
+        for class in 0..N_CORNER_CLASS {
+            assert_eq!(tables.corner_permutation.self_symmetries(class) & 1, 1);
+        }
+
+        // The LR mirror swaps clockwise and anticlockwise.
+        assert_eq!(tables.conjugate_move(Moves::U1, 1), Moves::U3);
+        assert_eq!(tables.conjugate_move(Moves::R2, 1), Moves::L2);
+        assert_eq!(tables.conjugate_move(Moves::F1, 0), Moves::F1);
+    }
+}
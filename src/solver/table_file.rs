@@ -0,0 +1,503 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Saving and loading of the generated move and pruning tables.
+//!
+//! Generating the tables takes a few seconds, so once built they are written
+//! to a cache directory and read back on the next start. Every file starts
+//! with a fixed size header:
+//!
+//! | Bytes | Contents                                            |
+//! |-------|-----------------------------------------------------|
+//! | 0-7   | The magic bytes `RUBIKTBL`                          |
+//! | 8-11  | `FORMAT_VERSION`, the layout of the file            |
+//! | 12-15 | `TABLE_VERSION`, bumped when any table changes      |
+//! | 16-19 | The `TableKind` id                                  |
+//! | 20-23 | The number of rows                                  |
+//! | 24-27 | The number of columns                               |
+//! | 28-31 | The size of each entry in bytes                     |
+//! | 32-39 | A FNV-1a checksum of the entries                    |
+//!
+//! followed by the entries themselves, little endian and row by row. A file
+//! that does not match what we expect in any way is treated as stale, and
+//! the table is rebuilt and saved again. The checksum is worked out once,
+//! when the file is written. Files read into memory are checked against it
+//! as they are read, but mapped files only when `CacheSettings::verify` is
+//! set, as checking means reading every page.
+
+use std::convert::TryInto;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::slice;
+use std::sync::Mutex;
+
+use memmap2::Mmap;
+
+const MAGIC: &[u8; 8] = b"RUBIKTBL";
+const FORMAT_VERSION: u32 = 1;
+/// Bump this whenever the contents of any table change, so old files are
+/// thrown away instead of giving wrong answers.
+const TABLE_VERSION: u32 = 2;
+const HEADER_LEN: usize = 40;
+
+/// Every table that can be saved, with the id written to its header.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TableKind {
+    TwistMove = 1,
+    FlipMove,
+    SliceMove,
+    CornerPermutationMove,
+    EdgePermutationMove,
+    SortedSliceMove,
+    CornerSortedSlicePruning,
+    FlipSliceClass,
+    FlipSliceRepresentative,
+    FlipSliceSelfSymmetry,
+    CornerClass,
+    CornerRepresentative,
+    CornerSelfSymmetry,
+    TwistConjugation,
+    EdgePermutationConjugation,
+    FlipSliceTwistPruning,
+    CornerEdgePruning,
+    CornerTwistPruning,
+    FlipSliceTwistDistance,
+    FlipSortedSliceClass,
+    FlipSortedSliceRepresentative,
+    FlipSortedSliceSelfSymmetry,
+    FlipSortedSliceTwistPruning,
+}
+
+impl TableKind {
+    /// The name of the file the table is saved in.
+    pub fn file_name(&self) -> String {
+        format!("{:?}.tbl", self).to_ascii_lowercase()
+    }
+}
+
+/// How tables are brought into memory.
+///
+/// * `Read` - The whole file is read into a `Vec`.
+/// * `Map` - The file is memory-mapped and pages are read in by the
+///     operating system as needed. The file must not be changed by anything
+///     else while it is mapped.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LoadMode {
+    Read,
+    Map,
+}
+
+/// Where and how tables are cached.
+///
+/// # Variables
+/// * `directory` - The directory tables are saved in, or `None` to always
+///     generate them and never touch the disk.
+/// * `mode` - How tables are loaded.
+/// * `verify` - Whether to check the checksum of mapped files when they are
+///     loaded. Files that are read are always checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheSettings {
+    pub directory: Option<PathBuf>,
+    pub mode: LoadMode,
+    pub verify: bool,
+}
+
+impl CacheSettings {
+    /// The default settings. The directory is `$RUBIKS_TABLE_DIR` if set,
+    /// otherwise `rubiks-solver` in the user's cache directory.
+    ///
+    /// # Return
+    /// * `CacheSettings`
+    pub fn new() -> CacheSettings {
+        let directory = if let Some(dir) = env::var_os("RUBIKS_TABLE_DIR") {
+            PathBuf::from(dir)
+        } else if let Some(dir) = env::var_os("XDG_CACHE_HOME") {
+            PathBuf::from(dir).join("rubiks-solver")
+        } else if let Some(dir) = env::var_os("HOME") {
+            PathBuf::from(dir).join(".cache").join("rubiks-solver")
+        } else {
+            env::temp_dir().join("rubiks-solver")
+        };
+
+        CacheSettings {
+            directory: Some(directory),
+            mode: LoadMode::Read,
+            verify: false,
+        }
+    }
+
+    /// The settings for tests, which must not fill the user's cache. The
+    /// tables are never saved, unless `$RUBIKS_TABLE_DIR` names a directory
+    /// to keep them in between runs.
+    ///
+    /// # Return
+    /// * `CacheSettings`
+    #[cfg(test)]
+    pub fn for_tests() -> CacheSettings {
+        CacheSettings {
+            directory: env::var_os("RUBIKS_TABLE_DIR").map(PathBuf::from),
+            mode: LoadMode::Read,
+            verify: false,
+        }
+    }
+
+    /// The default settings, changed by any of these command line options:
+    ///
+    /// * `--table-dir DIR` - Cache the tables in `DIR`.
+    /// * `--no-table-cache` - Always generate the tables.
+    /// * `--map-tables` - Memory-map the table files instead of reading them.
+    /// * `--verify-tables` - Check the checksum of mapped files.
+    ///
+    /// Other arguments are left for the caller.
+    ///
+    /// # Parameters
+    /// * `args` - The command line arguments, without the program name.
+    /// # Return
+    /// * `Result<CacheSettings, String>` - The settings, or what was wrong
+    ///     with the arguments.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<CacheSettings, String> {
+        let mut settings = CacheSettings::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--table-dir" => match args.next() {
+                    Some(dir) => settings.directory = Some(PathBuf::from(dir)),
+                    None => return Err("--table-dir needs a directory".to_string()),
+                },
+                "--no-table-cache" => settings.directory = None,
+                "--map-tables" => settings.mode = LoadMode::Map,
+                "--verify-tables" => settings.verify = true,
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+}
+
+static SETTINGS: Mutex<Option<CacheSettings>> = Mutex::new(None);
+
+/// Changes where and how tables are cached. Only affects tables that have
+/// not been loaded yet, so should be called before the first solve.
+///
+/// # Parameters
+/// * `settings` - The new `CacheSettings`.
+pub fn configure(settings: CacheSettings) {
+    *SETTINGS.lock().unwrap() = Some(settings);
+}
+
+/// The `CacheSettings` currently in use. Tests that have not configured
+/// any get `CacheSettings::for_tests`.
+pub fn settings() -> CacheSettings {
+    SETTINGS.lock().unwrap().clone().unwrap_or_else(default_settings)
+}
+
+#[cfg(not(test))]
+fn default_settings() -> CacheSettings {
+    CacheSettings::new()
+}
+
+#[cfg(test)]
+fn default_settings() -> CacheSettings {
+    CacheSettings::for_tests()
+}
+
+/// A value that can be stored in a table file.
+pub trait Entry: Copy {
+    fn write(self, out: &mut Vec<u8>);
+    fn read(bytes: &[u8]) -> Self;
+}
+
+impl Entry for u8 {
+    fn write(self, out: &mut Vec<u8>) {
+        out.push(self);
+    }
+    fn read(bytes: &[u8]) -> u8 {
+        bytes[0]
+    }
+}
+
+impl Entry for u16 {
+    fn write(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn read(bytes: &[u8]) -> u16 {
+        u16::from_le_bytes([bytes[0], bytes[1]])
+    }
+}
+
+impl Entry for u32 {
+    fn write(self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+    fn read(bytes: &[u8]) -> u32 {
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+/// The entries of a table, either owned or memory-mapped from a file.
+pub enum Storage<T> {
+    Owned(Vec<T>),
+    Mapped(Mmap, PhantomData<T>),
+}
+
+impl<T: Entry> Deref for Storage<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Storage::Owned(v) => v,
+            Storage::Mapped(m, _) => {
+                let bytes = &m[HEADER_LEN..];
+                // The map is page aligned and the header keeps the entries
+                // aligned after it. Only little endian targets map files.
+                unsafe {
+                    slice::from_raw_parts(
+                        bytes.as_ptr() as *const T,
+                        bytes.len() / mem::size_of::<T>(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Loads a table from the cache, or builds and saves it if the cached file
+/// is missing, stale or corrupt.
+///
+/// # Parameters
+/// * `kind` - Which table this is.
+/// * `rows` - The number of rows the table should have.
+/// * `columns` - The number of columns the table should have.
+/// * `build` - Generates the table, row by row.
+/// # Return
+/// * `Storage<T>` - The table entries.
+pub fn load_or_build<T: Entry, F: FnOnce() -> Vec<T>>(
+    kind: TableKind,
+    rows: usize,
+    columns: usize,
+    build: F,
+) -> Storage<T> {
+    load_or_build_with(&settings(), kind, rows, columns, build)
+}
+
+/// `load_or_build` with the given `CacheSettings` instead of the global ones.
+fn load_or_build_with<T: Entry, F: FnOnce() -> Vec<T>>(
+    settings: &CacheSettings,
+    kind: TableKind,
+    rows: usize,
+    columns: usize,
+    build: F,
+) -> Storage<T> {
+    let directory = match settings.directory {
+        Some(ref d) => d.clone(),
+        None => return Storage::Owned(build()),
+    };
+    let path = directory.join(kind.file_name());
+
+    let loaded = match settings.mode {
+        LoadMode::Map if cfg!(target_endian = "little") => map(&path, kind, rows, columns, settings.verify),
+        _ => read(&path, kind, rows, columns).map(Storage::Owned),
+    };
+    if let Some(storage) = loaded {
+        return storage;
+    }
+
+    let table = build();
+    // The cache only saves time, so a table that cannot be saved is built
+    // again next time instead.
+    let _ = save(&directory, kind, rows, columns, &table);
+    Storage::Owned(table)
+}
+
+/// Reads a whole table file into memory.
+fn read<T: Entry>(path: &PathBuf, kind: TableKind, rows: usize, columns: usize) -> Option<Vec<T>> {
+    let mut bytes = Vec::new();
+    File::open(path).ok()?.read_to_end(&mut bytes).ok()?;
+    check::<T>(&bytes, kind, rows, columns, true)?;
+
+    let size = mem::size_of::<T>();
+    Some(bytes[HEADER_LEN..].chunks(size).map(T::read).collect())
+}
+
+/// Memory-maps a table file. Unless `verify` is set only the header is
+/// checked, so the entries are left on disk until they are looked at.
+fn map<T: Entry>(
+    path: &PathBuf,
+    kind: TableKind,
+    rows: usize,
+    columns: usize,
+    verify: bool,
+) -> Option<Storage<T>> {
+    let file = File::open(path).ok()?;
+    let m = unsafe { Mmap::map(&file).ok()? };
+    check::<T>(&m, kind, rows, columns, verify)?;
+    Some(Storage::Mapped(m, PhantomData))
+}
+
+/// Checks a table file's header against what we expect, and the checksum
+/// against the entries if `verify` is set.
+fn check<T: Entry>(bytes: &[u8], kind: TableKind, rows: usize, columns: usize, verify: bool) -> Option<()> {
+    let size = mem::size_of::<T>();
+    if bytes.len() != HEADER_LEN + rows * columns * size || &bytes[0..8] != MAGIC {
+        return None;
+    }
+    let sum = if verify {
+        checksum(&bytes[HEADER_LEN..])
+    } else {
+        u64::from_le_bytes(bytes[32..40].try_into().unwrap())
+    };
+    let expected = header(kind, rows, columns, size, sum);
+    if bytes[..HEADER_LEN] == expected[..] {
+        Some(())
+    } else {
+        None
+    }
+}
+
+/// Writes a table file, going through a temporary file so a half written
+/// file is never left behind under the real name.
+fn save<T: Entry>(
+    directory: &PathBuf,
+    kind: TableKind,
+    rows: usize,
+    columns: usize,
+    table: &[T],
+) -> io::Result<()> {
+    let size = mem::size_of::<T>();
+    let mut entries = Vec::with_capacity(table.len() * size);
+    for entry in table.iter() {
+        entry.write(&mut entries);
+    }
+
+    fs::create_dir_all(directory)?;
+    let path = directory.join(kind.file_name());
+    let temp = directory.join(format!("{}.{}.tmp", kind.file_name(), std::process::id()));
+    {
+        let mut file = File::create(&temp)?;
+        file.write_all(&header(kind, rows, columns, size, checksum(&entries)))?;
+        file.write_all(&entries)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp, &path)
+}
+
+/// Builds the header of a table file.
+fn header(kind: TableKind, rows: usize, columns: usize, size: usize, sum: u64) -> [u8; HEADER_LEN] {
+    let mut h = [0_u8; HEADER_LEN];
+    h[0..8].copy_from_slice(MAGIC);
+    h[8..12].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    h[12..16].copy_from_slice(&TABLE_VERSION.to_le_bytes());
+    h[16..20].copy_from_slice(&(kind as u32).to_le_bytes());
+    h[20..24].copy_from_slice(&(rows as u32).to_le_bytes());
+    h[24..28].copy_from_slice(&(columns as u32).to_le_bytes());
+    h[28..32].copy_from_slice(&(size as u32).to_le_bytes());
+    h[32..40].copy_from_slice(&sum.to_le_bytes());
+    h
+}
+
+/// A 64 bit FNV-1a hash, to catch files that were cut short or damaged.
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes.iter() {
+        hash = (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings(name: &str, mode: LoadMode, verify: bool) -> CacheSettings {
+        let directory = env::temp_dir().join(format!("rubiks-table-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        CacheSettings {
+            directory: Some(directory),
+            mode,
+            verify,
+        }
+    }
+
+    fn load(settings: &CacheSettings, table: &[u16]) -> (Vec<u16>, bool) {
+        let mut built = false;
+        let storage = load_or_build_with(settings, TableKind::TwistMove, 3, 2, || {
+            built = true;
+            table.to_vec()
+        });
+        (storage.to_vec(), built)
+    }
+
+    #[test]
+    fn test_table_round_trip_and_rebuild() {
+        let table: Vec<u16> = vec![1, 2, 300, 4, 65535, 0];
+        for (name, mode) in [("read", LoadMode::Read), ("map", LoadMode::Map)].iter() {
+            let settings = temp_settings(name, *mode, true);
+            let path = settings.directory.clone().unwrap().join(TableKind::TwistMove.file_name());
+
+            assert_eq!(load(&settings, &table), (table.clone(), true));
+            assert_eq!(load(&settings, &table), (table.clone(), false));
+
+            // Damage one entry, the checksum no longer matches.
+            let mut bytes = fs::read(&path).unwrap();
+            bytes[HEADER_LEN] ^= 0xff;
+            fs::write(&path, &bytes).unwrap();
+            assert_eq!(load(&settings, &table), (table.clone(), true));
+
+            // An old table version is thrown away too.
+            let mut bytes = fs::read(&path).unwrap();
+            bytes[12] = bytes[12].wrapping_add(1);
+            fs::write(&path, &bytes).unwrap();
+            assert_eq!(load(&settings, &table), (table.clone(), true));
+            assert_eq!(load(&settings, &table), (table.clone(), false));
+
+            let _ = fs::remove_dir_all(settings.directory.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_mapped_tables_skip_checksum() {
+        let table: Vec<u16> = vec![1, 2, 300, 4, 65535, 0];
+        let settings = temp_settings("map-unverified", LoadMode::Map, false);
+        let path = settings.directory.clone().unwrap().join(TableKind::TwistMove.file_name());
+        assert_eq!(load(&settings, &table), (table.clone(), true));
+
+        // The entries are not read, so damage to them goes unnoticed.
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[HEADER_LEN] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        assert!(!load(&settings, &table).1);
+
+        // The header is still checked.
+        bytes[12] = bytes[12].wrapping_add(1);
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(load(&settings, &table), (table.clone(), true));
+
+        let _ = fs::remove_dir_all(settings.directory.unwrap());
+    }
+
+    #[test]
+    fn test_settings_from_args() {
+        let args = |a: &[&str]| CacheSettings::from_args(a.iter().map(|s| s.to_string()));
+        assert_eq!(args(&[]), Ok(CacheSettings::new()));
+
+        let settings = args(&["--other", "--table-dir", "/tmp/tables", "--map-tables", "--verify-tables"]).unwrap();
+        assert_eq!(settings.directory, Some(PathBuf::from("/tmp/tables")));
+        assert_eq!(settings.mode, LoadMode::Map);
+        assert!(settings.verify);
+
+        assert_eq!(args(&["--no-table-cache"]).unwrap().directory, None);
+        assert!(args(&["--table-dir"]).is_err());
+    }
+}
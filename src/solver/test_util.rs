@@ -0,0 +1,49 @@
+//! ***************************************************************************
+//! Rust Rubiks Cube Solver <https://github.com/Thief3/RubiksCubeSolver>
+//!
+//! Copyright 2018 by Malik Kissarli <kissarlim@gmail.com>
+//! Licensed under GNU General Public License 3.0 or later.
+//! Some rights reserved. See COPYING, AUTHORS.
+//!
+//! @license GPL-3.0+ <http://spdx.org/licenses/GPL-3.0+>
+//! ***************************************************************************
+//!
+//! Cubes and checks shared by the tests of the searches.
+
+use physical;
+use solver::table_file::{self, CacheSettings};
+use solver::{do_move, Moves};
+
+/// Keeps the tables the tests build out of the user's cache. Call it before
+/// anything that builds tables.
+pub fn setup() {
+    table_file::configure(CacheSettings::for_tests());
+}
+
+/// The cube a scramble leads to from solved.
+pub fn scrambled(scramble: &[Moves]) -> physical::Cube {
+    let mut rubiks = physical::Cube::new();
+    for movement in scramble.iter() {
+        rubiks = do_move(rubiks, *movement);
+    }
+    rubiks
+}
+
+/// The superflip, with every edge flipped in place. It looks the same from
+/// every side and is 20 moves from solved, so no search finds it quickly.
+pub fn superflip() -> physical::Cube {
+    let mut rubiks = physical::Cube::new();
+    for i in 0..12 {
+        rubiks.edges.orientation[i] = 1;
+    }
+    rubiks
+}
+
+/// Checks that a solution solves a cube.
+pub fn check(rubiks: &physical::Cube, solution: &[Moves]) {
+    let mut c = *rubiks;
+    for movement in solution.iter() {
+        c = do_move(c, *movement);
+    }
+    assert_eq!(c, physical::Cube::new());
+}
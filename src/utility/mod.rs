@@ -32,9 +32,35 @@ pub fn factorial(n: i64) -> i64 {
 /// # Output
 /// * `i64` - the binomial coefficent
 pub fn binomial(n: i64, k: i64) -> i64 {
-    if n > k {
+    if n >= k {
         factorial(n) / (factorial(k) * factorial(n - k))
     } else {
         0
     }
 }
+
+/// Calculates the greatest common divisor with Euclid's algorithm.
+///
+/// # Parameters
+/// * `a` - The first number.
+/// * `b` - The second number.
+/// # Outputs
+/// * `u64` - The largest number dividing both.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Calculates the least common multiple.
+///
+/// # Parameters
+/// * `a` - The first number.
+/// * `b` - The second number.
+/// # Outputs
+/// * `u64` - The smallest number both divide.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}